@@ -0,0 +1,108 @@
+//! 颜色统计：RGB / 亮度直方图、各通道均值与标准差、主色调
+
+use image::{ImageBuffer, Rgba};
+
+use crate::metrics::luma;
+
+/// 主色调的颜色数
+pub const PALETTE_SIZE : usize = 8; 
+/// 主色调量化时每个通道保留的位数
+const PALETTE_BITS : u32 = 3; 
+
+/// 统计结果；通道顺序为 R、G、B、亮度
+#[derive(Clone, Debug)]
+pub struct ColorStats {
+    pub histograms: [[u32; 256]; 4], 
+    pub mean: [f64; 4], 
+    pub stddev: [f64; 4], 
+    /// 主色调与所占比例，按比例从大到小
+    pub palette: Vec<([u8; 3], f32)>, 
+    pub size: [u32; 2], 
+}
+
+impl ColorStats {
+    pub const CHANNELS : [&'static str; 4] = ["R", "G", "B", "Luma"]; 
+
+    /// 统计不透明度以外的通道
+    pub fn compute(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> ColorStats {
+        let mut histograms = [[0u32; 256]; 4]; 
+        let mut sum = [0f64; 4]; 
+        let mut squared = [0f64; 4]; 
+        let buckets = 1usize << (PALETTE_BITS * 3); 
+        let mut counts = vec![0u32; buckets]; 
+        let mut sums = vec![[0u64; 3]; buckets]; 
+        for p in image.pixels() {
+            let l = luma(p); 
+            let values = [p[0] as f64, p[1] as f64, p[2] as f64, l]; 
+            for c in 0..4 {
+                histograms[c][values[c].round().min(255.) as usize] += 1; 
+                sum[c] += values[c]; 
+                squared[c] += values[c] * values[c]; 
+            }
+            let shift = 8 - PALETTE_BITS; 
+            let bucket = ((p[0] >> shift) as usize) << (PALETTE_BITS * 2) | ((p[1] >> shift) as usize) << PALETTE_BITS | (p[2] >> shift) as usize; 
+            counts[bucket] += 1; 
+            for c in 0..3 {
+                sums[bucket][c] += p[c] as u64; 
+            }
+        }
+        let pixels = (image.width() as f64 * image.height() as f64).max(1.); 
+        let mean = sum.map(|s| s / pixels); 
+        let mut stddev = [0f64; 4]; 
+        for c in 0..4 {
+            stddev[c] = (squared[c] / pixels - mean[c] * mean[c]).max(0.).sqrt(); 
+        }
+        let mut order : Vec<usize> = (0..buckets).filter(|&b| counts[b] > 0).collect(); 
+        order.sort_by(|&a, &b| counts[b].cmp(&counts[a])); 
+        let palette = order.into_iter().take(PALETTE_SIZE).map(|b| {
+            let n = counts[b] as u64; 
+            let color = [0, 1, 2].map(|c| (sums[b][c] / n) as u8); 
+            (color, counts[b] as f32 / pixels as f32)
+        }).collect(); 
+        ColorStats { histograms, mean, stddev, palette, size: [image.width(), image.height()] }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2 × 2：三个红色像素、一个蓝色像素
+    fn tiny() -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(2, 2, |x, y| if (x, y) == (1, 1) { Rgba([0, 0, 255, 255]) } else { Rgba([255, 0, 0, 128]) })
+    }
+
+    #[test]
+    fn histograms() {
+        let stats = ColorStats::compute(&tiny()); 
+        assert_eq!(stats.size, [2, 2]); 
+        assert_eq!(stats.histograms[0][255], 3); 
+        assert_eq!(stats.histograms[0][0], 1); 
+        assert_eq!(stats.histograms[1][0], 4); 
+        assert_eq!(stats.histograms[2][0], 3); 
+        assert_eq!(stats.histograms[2][255], 1); 
+        // 亮度：红 76.245，蓝 29.07
+        assert_eq!(stats.histograms[3][76], 3); 
+        assert_eq!(stats.histograms[3][29], 1); 
+        for histogram in &stats.histograms {
+            assert_eq!(histogram.iter().sum::<u32>(), 4); 
+        }
+    }
+
+    #[test]
+    fn mean_and_stddev() {
+        let stats = ColorStats::compute(&tiny()); 
+        assert_eq!(stats.mean[..3], [191.25, 0., 63.75]); 
+        assert!((stats.mean[3] - (3. * 76.245 + 29.07) / 4.).abs() < 1e-9); 
+        let expected = 255. * 3f64.sqrt() / 4.; 
+        assert!((stats.stddev[0] - expected).abs() < 1e-9, "{}", stats.stddev[0]); 
+        assert_eq!(stats.stddev[1], 0.); 
+        assert!((stats.stddev[2] - expected).abs() < 1e-9, "{}", stats.stddev[2]); 
+    }
+
+    #[test]
+    fn palette() {
+        let stats = ColorStats::compute(&tiny()); 
+        assert_eq!(stats.palette, vec![([255, 0, 0], 0.75), ([0, 0, 255], 0.25)]); 
+    }
+}
@@ -0,0 +1,221 @@
+//! 前后对比：分割滑块、闪烁切换、并排显示；两幅图像尺寸不同时把后者缩放到前者的尺寸
+
+use std::time::Duration;
+
+use eframe::egui::{self, Color32, Pos2, Rect, Sense, Stroke, TextureOptions, Vec2};
+use eframe::epaint::{ColorImage, TextureHandle};
+use futures::channel::oneshot;
+use image::{ImageBuffer, Rgba};
+
+use crate::image_loader;
+
+/// 闪烁模式的切换间隔
+pub const FLICKER_INTERVAL : Duration = Duration::from_millis(600); 
+
+/// 对比方式
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompareMode {
+    Split, 
+    Flicker, 
+    SideBySide, 
+}
+
+impl CompareMode {
+    pub const ALL : [CompareMode; 3] = [CompareMode::Split, CompareMode::Flicker, CompareMode::SideBySide]; 
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompareMode::Split => "Split", 
+            CompareMode::Flicker => "Flicker", 
+            CompareMode::SideBySide => "Side by Side", 
+        }
+    }
+}
+
+/// 后台读取的一对图像；after 已缩放到 before 的尺寸
+pub struct ComparePair {
+    pub before: ImageBuffer<Rgba<u8>, Vec<u8>>, 
+    pub after: ImageBuffer<Rgba<u8>, Vec<u8>>, 
+    /// 原图尺寸
+    pub before_size: [u32; 2], 
+    pub after_size: [u32; 2], 
+}
+
+/// 读取两幅图像，缩小到最长边不超过 max_side，并把 after 缩放到 before 的尺寸
+pub fn load_pair(before: &str, after: &str, max_side: u32) -> image::ImageResult<ComparePair> {
+    let before = image_loader::open_oriented(before)?.to_rgba8(); 
+    let after = image_loader::open_oriented(after)?.to_rgba8(); 
+    let before_size = [before.width(), before.height()]; 
+    let after_size = [after.width(), after.height()]; 
+    let before = image_loader::preview(before, max_side); 
+    let after = image_loader::match_size(after, before.dimensions()); 
+    Ok(ComparePair { before, after, before_size, after_size })
+}
+
+/// 对比窗口状态
+pub struct Compare {
+    pub before_path: String, 
+    pub after_path: String, 
+    pub before: Option<TextureHandle>, 
+    pub after: Option<TextureHandle>, 
+    pub rx: Option<oneshot::Receiver<ComparePair>>, 
+    pub before_size: [u32; 2], 
+    pub after_size: [u32; 2], 
+    pub mode: CompareMode, 
+    /// 分割线位置（0 - 1，左侧为 before）
+    pub split: f32, 
+    /// 闪烁模式自动切换；关闭时点击画面手动切换
+    pub flicker_auto: bool, 
+    /// 闪烁模式当前显示 after
+    pub showing_after: bool, 
+    pub error: Option<String>, 
+}
+
+impl Compare {
+    /// 在后台读取两幅图像
+    pub fn open(before_path: String, after_path: String, max_side: usize) -> Compare {
+        let (tx, rx) = oneshot::channel(); 
+        let (b, a) = (before_path.clone(), after_path.clone()); 
+        std::thread::spawn(move || {
+            match load_pair(&b, &a, max_side as u32) {
+                Ok(pair) => {
+                    let _ = tx.send(pair); 
+                }
+                Err(e) => {
+                    eprintln!("Error: {:?}", e); 
+                }
+            }
+        }); 
+        Compare {
+            before_path, 
+            after_path, 
+            before: None, 
+            after: None, 
+            rx: Some(rx), 
+            before_size: [0, 0], 
+            after_size: [0, 0], 
+            mode: CompareMode::Split, 
+            split: 0.5, 
+            flicker_auto: true, 
+            showing_after: false, 
+            error: None, 
+        }
+    }
+
+    /// 检查后台读取结果
+    fn poll(&mut self, ctx: &egui::Context) {
+        let Some(ref mut rx) = self.rx else {
+            return ; 
+        }; 
+        match rx.try_recv() {
+            Ok(None) => ctx.request_repaint(), 
+            Ok(Some(pair)) => {
+                let upload = |name: &str, ib: &ImageBuffer<Rgba<u8>, Vec<u8>>| {
+                    let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], ib); 
+                    ctx.load_texture(name, ci, TextureOptions::LINEAR)
+                }; 
+                self.before = Some(upload("compare:before", &pair.before)); 
+                self.after = Some(upload("compare:after", &pair.after)); 
+                self.before_size = pair.before_size; 
+                self.after_size = pair.after_size; 
+                self.rx = None; 
+            }
+            Err(_) => {
+                self.error = Some(format!("Cannot read {} or {}", self.before_path, self.after_path)); 
+                self.rx = None; 
+            }
+        }
+    }
+
+    /// 显示窗口；open 为 false 时窗口已关闭
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        self.poll(ctx); 
+        egui::Window::new("Compare").id(egui::Id::new("image_compare")).open(open)
+            .default_size([800., 600.]).resizable(true).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for mode in CompareMode::ALL {
+                    ui.selectable_value(&mut self.mode, mode, mode.name()); 
+                }
+                if self.mode == CompareMode::Flicker {
+                    ui.separator(); 
+                    ui.checkbox(&mut self.flicker_auto, "Auto"); 
+                }
+            }); 
+            if self.before_size != self.after_size && self.after.is_some() {
+                ui.label(format!("After resized from {} × {} to {} × {}", 
+                    self.after_size[0], self.after_size[1], self.before_size[0], self.before_size[1])); 
+            }
+            ui.separator(); 
+            if let Some(ref e) = self.error {
+                ui.colored_label(Color32::RED, e); 
+                return ; 
+            }
+            let (Some(before), Some(after)) = (self.before.clone(), self.after.clone()) else {
+                ui.centered_and_justified(|ui| ui.spinner()); 
+                return ; 
+            }; 
+            let (rect, response) = ui.allocate_exact_size(ui.available_size().max(Vec2::splat(64.)), Sense::click_and_drag()); 
+            let painter = ui.painter_at(rect); 
+            painter.rect_filled(rect, 0., ui.visuals().extreme_bg_color); 
+            let full_uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1., 1.)); 
+            let label = |rect: Rect, text: &str| {
+                painter.text(rect.left_top() + Vec2::splat(4.), egui::Align2::LEFT_TOP, text, 
+                    egui::FontId::proportional(14.), Color32::WHITE); 
+            }; 
+            match self.mode {
+                CompareMode::Split => {
+                    let image_rect = letterbox(rect, before.size_vec2()); 
+                    if let Some(pos) = response.interact_pointer_pos() {
+                        self.split = ((pos.x - image_rect.left()) / image_rect.width()).clamp(0., 1.); 
+                    }
+                    let x = image_rect.left() + image_rect.width() * self.split; 
+                    let left = Rect::from_min_max(image_rect.min, Pos2::new(x, image_rect.max.y)); 
+                    let right = Rect::from_min_max(Pos2::new(x, image_rect.min.y), image_rect.max); 
+                    painter.image(before.id(), left, Rect::from_min_max(Pos2::ZERO, Pos2::new(self.split, 1.)), Color32::WHITE); 
+                    painter.image(after.id(), right, Rect::from_min_max(Pos2::new(self.split, 0.), Pos2::new(1., 1.)), Color32::WHITE); 
+                    painter.line_segment([Pos2::new(x, image_rect.top()), Pos2::new(x, image_rect.bottom())], Stroke::new(2., Color32::WHITE)); 
+                    label(image_rect, "Before"); 
+                    painter.text(image_rect.right_top() + Vec2::new(-4., 4.), egui::Align2::RIGHT_TOP, "After", 
+                        egui::FontId::proportional(14.), Color32::WHITE); 
+                    if response.hovered() {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal); 
+                    }
+                }
+                CompareMode::Flicker => {
+                    if self.flicker_auto {
+                        let time = ui.input(|i| i.time); 
+                        let interval = FLICKER_INTERVAL.as_secs_f64(); 
+                        self.showing_after = (time / interval) as u64 % 2 == 1; 
+                        ui.ctx().request_repaint_after(FLICKER_INTERVAL / 4); 
+                    } else if response.clicked() {
+                        self.showing_after = !self.showing_after; 
+                    }
+                    let image_rect = letterbox(rect, before.size_vec2()); 
+                    let (texture, text) = if self.showing_after { (&after, "After") } else { (&before, "Before") }; 
+                    painter.image(texture.id(), image_rect, full_uv, Color32::WHITE); 
+                    label(image_rect, text); 
+                }
+                CompareMode::SideBySide => {
+                    let gap = ui.spacing().item_spacing.x; 
+                    let half = Vec2::new((rect.width() - gap) / 2., rect.height()); 
+                    let left = Rect::from_min_size(rect.min, half); 
+                    let right = Rect::from_min_size(Pos2::new(left.right() + gap, rect.top()), half); 
+                    let (l, r) = (letterbox(left, before.size_vec2()), letterbox(right, after.size_vec2())); 
+                    painter.image(before.id(), l, full_uv, Color32::WHITE); 
+                    painter.image(after.id(), r, full_uv, Color32::WHITE); 
+                    label(l, "Before"); 
+                    label(r, "After"); 
+                }
+            }
+        }); 
+    }
+}
+
+/// 在 rect 中按 size 的宽高比居中放置（留黑边）
+pub fn letterbox(rect: Rect, size: Vec2) -> Rect {
+    if size.x <= 0. || size.y <= 0. {
+        return rect; 
+    }
+    let scale = (rect.width() / size.x).min(rect.height() / size.y); 
+    Rect::from_center_size(rect.center(), size * scale)
+}
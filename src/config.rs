@@ -0,0 +1,369 @@
+//! 应用配置：内置默认值 < 用户配置 < 项目配置 < 命令行 `--config <path>`
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::image_format::{OutputFormat, OutputSettings, DEFAULT_QUALITY, INPUT_EXTENSIONS};
+use crate::script_execution::DEFAULT_OUTPUT;
+use crate::script_root::{NATIVE_SCRIPT_ROOT, PY_SCRIPT_ROOT};
+use crate::shortcuts::Shortcuts;
+
+/// 项目配置文件
+pub const PROJECT_CONFIG_FILE : &str = "./image-transfer.toml"; 
+/// 用户配置文件（相对于用户配置目录）
+pub const USER_CONFIG_FILE : &str = "image-transfer/config.toml"; 
+
+/// 配置项，缺省的键使用默认值
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 初始窗口大小 [宽, 高]
+    pub window_size: [f32; 2], 
+    /// Python 脚本目录
+    pub py_script_dir: String, 
+    /// Native 脚本目录
+    pub native_script_dir: String, 
+    /// 输出文件，扩展名决定默认输出格式
+    pub output: String, 
+    /// 默认输出质量（1 - 100）
+    pub output_quality: u8, 
+    /// 默认使用第一个输入图像的格式作为输出格式
+    pub output_follow_input: bool, 
+    /// Python 脚本列表刷新间隔（毫秒）
+    pub py_script_flush_ms: u64, 
+    /// Native 脚本列表刷新间隔（毫秒）
+    pub native_script_flush_ms: u64, 
+    /// 后台线程轮询间隔（毫秒）
+    pub time_slice_ms: u64, 
+    /// 默认 Python 解释器；None 使用 ./python
+    pub python: Option<String>, 
+    /// 预览纹理的最长边（像素）；执行时仍使用原图
+    pub preview_size: u32, 
+    /// 文件选择框中的图像扩展名
+    pub image_extensions: Vec<String>, 
+    /// 快捷键，动作名称到按键组合（如 `execute = "Ctrl+Enter"`）；未列出的动作使用默认快捷键
+    pub shortcuts: BTreeMap<String, String>, 
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            window_size: [1024.0, 768.0], 
+            py_script_dir: PY_SCRIPT_ROOT.to_string(), 
+            native_script_dir: NATIVE_SCRIPT_ROOT.to_string(), 
+            output: DEFAULT_OUTPUT.to_string(), 
+            output_quality: DEFAULT_QUALITY, 
+            output_follow_input: false, 
+            py_script_flush_ms: 1000, 
+            native_script_flush_ms: 1000, 
+            time_slice_ms: 100, 
+            python: None, 
+            preview_size: 1024, 
+            image_extensions: INPUT_EXTENSIONS.iter().map(|s| s.to_string()).collect(), 
+            shortcuts: BTreeMap::new(), 
+        }
+    }
+}
+
+/// 所有合法的键
+const KEYS : &[&str] = &[
+    "window_size", "py_script_dir", "native_script_dir", "output", "output_quality", "output_follow_input", 
+    "py_script_flush_ms", "native_script_flush_ms", "time_slice_ms", 
+    "python", "preview_size", "image_extensions", "shortcuts", 
+]; 
+
+/// 用户配置目录下的配置文件
+pub fn user_config_path() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let base = std::env::var_os("APPDATA").map(PathBuf::from); 
+    #[cfg(not(target_os = "windows"))]
+    let base = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| Path::new(&h).join(".config"))); 
+    base.map(|b| b.join(USER_CONFIG_FILE))
+}
+
+/// 脚本额外参数的选项；它的值原样传给脚本，不当作本程序的选项
+pub const ARGS_OPTION : &str = "--args"; 
+/// 之后的内容都属于脚本额外参数
+pub const OPTIONS_END : &str = "--"; 
+
+/// 查找本程序的选项 name 的位置；跳过 `--args` 的值，`--` 之后不再查找
+pub fn find_option(args: &[String], name: &str) -> Option<usize> {
+    let mut i = 0; 
+    while i < args.len() {
+        match args[i].as_str() {
+            OPTIONS_END => return None, 
+            a if a == name => return Some(i), 
+            ARGS_OPTION => i += 2, 
+            _ => i += 1, 
+        }
+    }
+    None
+}
+
+/// 从参数中取出 `--config <path>`，返回剩余参数与配置文件路径；缺少路径时返回错误
+pub fn take_config_arg(args: Vec<String>) -> Result<(Vec<String>, Option<String>), String> {
+    let mut rest = Vec::with_capacity(args.len()); 
+    let mut path = None; 
+    let mut args = args.into_iter(); 
+    while let Some(a) = args.next() {
+        if a == OPTIONS_END {
+            rest.push(a); 
+            break; 
+        } else if a == ARGS_OPTION {
+            rest.push(a); 
+            rest.extend(args.next()); 
+        } else if a == "--config" {
+            match args.next() {
+                Some(p) if !p.starts_with("--") => path = Some(p), 
+                _ => return Err("missing value for --config".to_string()), 
+            }
+        } else if let Some(p) = a.strip_prefix("--config=") {
+            if p.is_empty() {
+                return Err("missing value for --config".to_string()); 
+            }
+            path = Some(p.to_string()); 
+        } else {
+            rest.push(a); 
+        }
+    }
+    rest.extend(args); 
+    Ok((rest, path))
+}
+
+impl Config {
+    pub fn py_script_flush_time(&self) -> Duration {
+        Duration::from_millis(self.py_script_flush_ms)
+    }
+
+    pub fn native_script_flush_time(&self) -> Duration {
+        Duration::from_millis(self.native_script_flush_ms)
+    }
+
+    /// 由 `output` 的扩展名、`output_quality` 与 `output_follow_input` 得到的默认输出设置
+    pub fn output_settings(&self) -> OutputSettings {
+        OutputSettings {
+            quality: self.output_quality, 
+            follow_input: self.output_follow_input, 
+            ..OutputSettings::from_path(&self.output)
+        }
+    }
+
+    pub fn time_slice(&self) -> Duration {
+        Duration::from_millis(self.time_slice_ms)
+    }
+
+    /// 应用了 `shortcuts` 的快捷键；无法识别的项已在读取时报告
+    pub fn shortcuts(&self) -> Shortcuts {
+        Shortcuts::from_config(&self.shortcuts).0
+    }
+
+    /// 按层级读取配置，返回配置与所有问题（未知的键、类型错误、非法取值）
+    pub fn load(cli_path: Option<&str>) -> (Config, Vec<String>) {
+        let mut files : Vec<(PathBuf, bool)> = Vec::new(); 
+        if let Some(p) = user_config_path() {
+            files.push((p, false)); 
+        }
+        files.push((PathBuf::from(PROJECT_CONFIG_FILE), false)); 
+        if let Some(p) = cli_path {
+            files.push((PathBuf::from(p), true)); 
+        }
+        Config::load_files(files)
+    }
+
+    /// 依次读取配置文件，后面的覆盖前面的；required 的文件不存在时也报告问题
+    fn load_files(files: Vec<(PathBuf, bool)>) -> (Config, Vec<String>) {
+        let mut problems = Vec::new(); 
+        let mut merged = toml::Table::new(); 
+        for (path, required) in files {
+            let text = match std::fs::read_to_string(&path) {
+                Ok(t) => t, 
+                Err(e) => {
+                    if required || e.kind() != std::io::ErrorKind::NotFound {
+                        problems.push(format!("{}: {}", path.display(), e)); 
+                    }
+                    continue; 
+                }
+            }; 
+            let table = match toml::from_str::<toml::Table>(&text) {
+                Ok(t) => t, 
+                Err(e) => {
+                    problems.push(format!("{}: {}", path.display(), e)); 
+                    continue; 
+                }
+            }; 
+            for (key, value) in table {
+                if !KEYS.contains(&key.as_str()) {
+                    problems.push(format!("{}: unknown key `{}`", path.display(), key)); 
+                    continue; 
+                }
+                // 逐个键检查类型，错误的键保留默认值
+                let mut single = toml::Table::new(); 
+                single.insert(key.clone(), value.clone()); 
+                if let Err(e) = toml::Value::Table(single).try_into::<Config>() {
+                    problems.push(format!("{}: invalid value for `{}`: {}", path.display(), key, e.message())); 
+                    continue; 
+                }
+                // 快捷键逐项覆盖，而不是整表替换
+                if let (Some(toml::Value::Table(old)), toml::Value::Table(new)) = (merged.get_mut(&key), &value) {
+                    old.extend(new.clone()); 
+                    continue; 
+                }
+                merged.insert(key, value); 
+            }
+        }
+        let mut config : Config = toml::Value::Table(merged).try_into().unwrap_or_default(); 
+        config.validate(&mut problems); 
+        (config, problems)
+    }
+
+    /// 检查取值范围，非法的项恢复为默认值
+    fn validate(&mut self, problems: &mut Vec<String>) {
+        let default = Config::default(); 
+        if !self.window_size.iter().all(|v| v.is_finite() && *v >= 100.) {
+            problems.push(format!("`window_size` must be at least [100, 100], got {:?}", self.window_size)); 
+            self.window_size = default.window_size; 
+        }
+        for (key, value, default) in [
+            ("py_script_flush_ms", &mut self.py_script_flush_ms, default.py_script_flush_ms), 
+            ("native_script_flush_ms", &mut self.native_script_flush_ms, default.native_script_flush_ms), 
+            ("time_slice_ms", &mut self.time_slice_ms, default.time_slice_ms), 
+        ] {
+            if *value == 0 {
+                problems.push(format!("`{}` must be positive", key)); 
+                *value = default; 
+            }
+        }
+        if self.output.trim().is_empty() {
+            problems.push("`output` must not be empty".to_string()); 
+            self.output = default.output; 
+        } else if !OutputFormat::from_path(&self.output).is_some_and(|f| f.can_encode()) {
+            problems.push(format!("`output` has an unsupported extension: {}", self.output)); 
+            self.output = default.output; 
+        }
+        if !(1..=100).contains(&self.output_quality) {
+            problems.push(format!("`output_quality` must be within 1 - 100, got {}", self.output_quality)); 
+            self.output_quality = default.output_quality; 
+        }
+        if !(64..=8192).contains(&self.preview_size) {
+            problems.push(format!("`preview_size` must be within 64 - 8192, got {}", self.preview_size)); 
+            self.preview_size = default.preview_size; 
+        }
+        if self.image_extensions.is_empty() {
+            problems.push("`image_extensions` must not be empty".to_string()); 
+            self.image_extensions = default.image_extensions; 
+        }
+        problems.extend(Shortcuts::from_config(&self.shortcuts).1); 
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &[&str]) -> Vec<String> {
+        s.iter().map(|a| a.to_string()).collect()
+    }
+
+    /// 把 text 写入临时目录中的配置文件
+    fn config_file(name: &str, text: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("image-transfer-config-test-{}", std::process::id())); 
+        std::fs::create_dir_all(&dir).unwrap(); 
+        let path = dir.join(name); 
+        std::fs::write(&path, text).unwrap(); 
+        path
+    }
+
+    #[test]
+    fn takes_config_arg() {
+        let (rest, path) = take_config_arg(args(&["--headless", "--config", "c.toml", "--script", "s"])).unwrap(); 
+        assert_eq!(rest, args(&["--headless", "--script", "s"])); 
+        assert_eq!(path.as_deref(), Some("c.toml")); 
+        let (rest, path) = take_config_arg(args(&["--config=c.toml", "x"])).unwrap(); 
+        assert_eq!((rest, path.as_deref()), (args(&["x"]), Some("c.toml"))); 
+    }
+
+    #[test]
+    fn skips_the_value_of_args() {
+        let (rest, path) = take_config_arg(args(&["--headless", "--args", "-w 2", "--config", "c.toml"])).unwrap(); 
+        assert_eq!(rest, args(&["--headless", "--args", "-w 2"])); 
+        assert_eq!(path.as_deref(), Some("c.toml")); 
+        let (rest, path) = take_config_arg(args(&["--args", "--config", "x"])).unwrap(); 
+        assert_eq!(rest, args(&["--args", "--config", "x"])); 
+        assert_eq!(path, None); 
+    }
+
+    #[test]
+    fn stops_at_end_of_options() {
+        let (rest, path) = take_config_arg(args(&["a", "--", "--config", "c.toml"])).unwrap(); 
+        assert_eq!(rest, args(&["a", "--", "--config", "c.toml"])); 
+        assert_eq!(path, None); 
+    }
+
+    #[test]
+    fn reports_missing_config_value() {
+        assert!(take_config_arg(args(&["--config"])).is_err()); 
+        assert!(take_config_arg(args(&["--config", "--headless"])).is_err()); 
+        assert!(take_config_arg(args(&["--config="])).is_err()); 
+    }
+
+    #[test]
+    fn finds_options_outside_script_arguments() {
+        assert_eq!(find_option(&args(&["--args", "x", "--headless"]), "--headless"), Some(2)); 
+        assert_eq!(find_option(&args(&["--args", "--headless"]), "--headless"), None); 
+        assert_eq!(find_option(&args(&["--", "--headless"]), "--headless"), None); 
+        assert_eq!(find_option(&args(&["--script", "s", "--headless"]), "--headless"), Some(2)); 
+        assert_eq!(find_option(&args(&[]), "--headless"), None); 
+    }
+
+    #[test]
+    fn later_files_override_earlier_ones() {
+        let user = config_file("user.toml", "output_quality = 50\npreview_size = 512\n[shortcuts]\nexecute = \"F9\"\ncancel = \"F10\"\n"); 
+        let cli = config_file("cli.toml", "output_quality = 70\n[shortcuts]\ncancel = \"F11\"\n"); 
+        let (config, problems) = Config::load_files(vec![(user, false), (cli, true)]); 
+        assert!(problems.is_empty(), "{:?}", problems); 
+        assert_eq!(config.output_quality, 70); 
+        assert_eq!(config.preview_size, 512); 
+        assert_eq!(config.shortcuts.get("execute").map(String::as_str), Some("F9")); 
+        assert_eq!(config.shortcuts.get("cancel").map(String::as_str), Some("F11")); 
+    }
+
+    #[test]
+    fn reports_file_and_key_problems() {
+        let base = config_file("base.toml", "preview_size = 512\n"); 
+        let bad = config_file("bad.toml", "preview_size = \"big\"\nno_such_key = 1\n"); 
+        let missing = std::env::temp_dir().join("image-transfer-config-test-missing.toml"); 
+        let (config, problems) = Config::load_files(vec![(base, false), (bad, false), (missing.clone(), false)]); 
+        assert_eq!(config.preview_size, 512); 
+        assert_eq!(problems.len(), 2, "{:?}", problems); 
+        let (_, problems) = Config::load_files(vec![(missing, true)]); 
+        assert_eq!(problems.len(), 1); 
+    }
+
+    #[test]
+    fn validate_restores_defaults() {
+        let mut config = Config {
+            window_size: [10., f32::NAN], 
+            output: "out.webp".to_string(), 
+            output_quality: 0, 
+            time_slice_ms: 0, 
+            preview_size: 16, 
+            ..Config::default()
+        }; 
+        let mut problems = Vec::new(); 
+        config.validate(&mut problems); 
+        let default = Config::default(); 
+        assert_eq!(problems.len(), 5, "{:?}", problems); 
+        assert_eq!(config.window_size, default.window_size); 
+        assert_eq!(config.output, default.output); 
+        assert_eq!(config.output_quality, default.output_quality); 
+        assert_eq!(config.time_slice_ms, default.time_slice_ms); 
+        assert_eq!(config.preview_size, default.preview_size); 
+        let mut problems = Vec::new(); 
+        Config::default().validate(&mut problems); 
+        assert!(problems.is_empty(), "{:?}", problems); 
+    }
+}
@@ -0,0 +1,130 @@
+//! 差异窗口：选择两幅图像，在后台计算差值热图与指标
+
+use eframe::egui::{self, Color32, TextureOptions};
+use eframe::epaint::{ColorImage, TextureHandle};
+use futures::channel::oneshot;
+
+use crate::image_loader;
+use crate::metrics::{self, Difference, Metrics};
+
+/// 差异窗口状态
+pub struct DiffView {
+    /// 选中的两幅图像（文件路径）
+    pub a: Option<String>, 
+    pub b: Option<String>, 
+    /// 热图放大倍数
+    pub gain: f32, 
+    pub rx: Option<oneshot::Receiver<Result<Difference, String>>>, 
+    pub result: Option<(Metrics, TextureHandle)>, 
+    pub error: Option<String>, 
+}
+
+impl DiffView {
+    pub fn new(a: Option<String>, b: Option<String>) -> DiffView {
+        DiffView { a, b, gain: 4., rx: None, result: None, error: None }
+    }
+
+    /// 在后台读取并比较；b 的尺寸不同时先缩放到 a 的尺寸
+    pub fn compute(&mut self) {
+        let (Some(a), Some(b)) = (self.a.clone(), self.b.clone()) else {
+            return ; 
+        }; 
+        let gain = self.gain; 
+        let (tx, rx) = oneshot::channel(); 
+        std::thread::spawn(move || {
+            let load = |p: &str| image_loader::open_oriented(p).map(|i| i.to_rgba8()).map_err(|e| format!("{}: {}", p, e)); 
+            let result = load(&a).and_then(|a| load(&b).map(|b| (a, b))).map(|(a, b)| {
+                let b = image_loader::match_size(b, a.dimensions()); 
+                metrics::compare(&a, &b, gain)
+            }).and_then(|d| d.ok_or_else(|| "Empty image".to_string())); 
+            let _ = tx.send(result); 
+        }); 
+        self.rx = Some(rx); 
+        self.error = None; 
+    }
+
+    /// 检查后台计算结果；热图缩小到最长边不超过 max_side 后上传
+    fn poll(&mut self, ctx: &egui::Context) {
+        let Some(ref mut rx) = self.rx else {
+            return ; 
+        }; 
+        match rx.try_recv() {
+            Ok(None) => ctx.request_repaint(), 
+            Ok(Some(Ok(d))) => {
+                let max_side = ctx.input(|i| i.max_texture_side) as u32; 
+                let ib = image_loader::preview(d.heatmap, max_side); 
+                let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], &ib); 
+                self.result = Some((d.metrics, ctx.load_texture("diff:heatmap", ci, TextureOptions::LINEAR))); 
+                self.rx = None; 
+            }
+            Ok(Some(Err(e))) => {
+                self.error = Some(e); 
+                self.rx = None; 
+            }
+            Err(_) => {
+                self.rx = None; 
+            }
+        }
+    }
+
+    /// 显示窗口；sources 为可选的图像（名称，文件路径）
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool, sources: &[(String, String)]) {
+        self.poll(ctx); 
+        egui::Window::new("Difference").id(egui::Id::new("image_difference")).open(open)
+            .default_size([640., 560.]).resizable(true).show(ctx, |ui| {
+            egui::Grid::new("diff_sources").num_columns(2).show(ui, |ui| {
+                for (label, selected) in [("A: ", &mut self.a), ("B: ", &mut self.b)] {
+                    ui.label(label); 
+                    let text = sources.iter().find(|(_, p)| Some(p) == selected.as_ref())
+                        .map(|(n, _)| n.clone())
+                        .or_else(|| selected.clone())
+                        .unwrap_or_else(|| "Select".to_string()); 
+                    egui::ComboBox::from_id_source(label).selected_text(text).width(360.).show_ui(ui, |ui| {
+                        for (name, path) in sources {
+                            ui.selectable_value(selected, Some(path.clone()), name).on_hover_text(path.as_str()); 
+                        }
+                    }); 
+                    ui.end_row(); 
+                }
+                ui.label("Heatmap Gain: "); 
+                ui.add(egui::Slider::new(&mut self.gain, 1. ..=32.).logarithmic(true)); 
+                ui.end_row(); 
+            }); 
+            let running = self.rx.is_some(); 
+            ui.horizontal(|ui| {
+                let ready = self.a.is_some() && self.b.is_some() && !running; 
+                if ui.add_enabled(ready, egui::Button::new("Compute")).clicked() {
+                    self.compute(); 
+                }
+                if running {
+                    ui.spinner(); 
+                }
+            }); 
+            if let Some(ref e) = self.error {
+                ui.colored_label(Color32::RED, e); 
+            }
+            let Some((ref m, ref texture)) = self.result else {
+                return ; 
+            }; 
+            ui.separator(); 
+            egui::Grid::new("diff_metrics").num_columns(2).show(ui, |ui| {
+                ui.label("PSNR: "); 
+                ui.label(if m.psnr.is_infinite() { "∞ (identical)".to_string() } else { format!("{:.2} dB", m.psnr) }); 
+                ui.end_row(); 
+                ui.label("SSIM: "); 
+                ui.label(format!("{:.4}", m.ssim)); 
+                ui.end_row(); 
+                ui.label("Mean Color Shift: "); 
+                ui.label(format!("R {:+.2}  G {:+.2}  B {:+.2}", m.mean_shift[0], m.mean_shift[1], m.mean_shift[2])); 
+                ui.end_row(); 
+                ui.label("Max Difference: "); 
+                ui.label(m.max_difference.to_string()); 
+                ui.end_row(); 
+            }); 
+            let size = texture.size_vec2(); 
+            let available = ui.available_size(); 
+            let scale = (available.x / size.x).min(available.y / size.y).min(1.); 
+            ui.image(texture, size * scale); 
+        }); 
+    }
+}
@@ -0,0 +1,68 @@
+//! 用系统默认程序打开文件，或在文件管理器中显示文件
+
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// 用系统默认的查看器打开文件
+pub fn open(path: &Path) -> io::Result<()> {
+    let path = crate::file_uri::absolute(path)?; 
+    open_command(&path).spawn().map(|_| ())
+}
+
+/// 在文件管理器中显示并选中文件
+pub fn reveal(path: &Path) -> io::Result<()> {
+    let path = crate::file_uri::absolute(path)?; 
+    reveal_command(&path).spawn().map(|_| ())
+}
+
+/// 不经过 cmd，文件名中的 `&`、`^`、`|` 不会被解释
+#[cfg(target_os = "windows")]
+fn open_command(path: &Path) -> Command {
+    let mut c = Command::new("explorer"); 
+    c.arg(path); 
+    c
+}
+
+#[cfg(target_os = "windows")]
+fn reveal_command(path: &Path) -> Command {
+    let mut select = std::ffi::OsString::from("/select,"); 
+    select.push(path); 
+    let mut c = Command::new("explorer"); 
+    c.arg(select); 
+    c
+}
+
+#[cfg(target_os = "macos")]
+fn open_command(path: &Path) -> Command {
+    let mut c = Command::new("open"); 
+    c.arg(path); 
+    c
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_command(path: &Path) -> Command {
+    let mut c = Command::new("open"); 
+    c.arg("-R").arg(path); 
+    c
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn open_command(path: &Path) -> Command {
+    let mut c = Command::new("xdg-open"); 
+    c.arg(path); 
+    c
+}
+
+/// 通过 FileManager1 D-Bus 接口选中文件；大多数桌面环境的文件管理器都实现了它
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn reveal_command(path: &Path) -> Command {
+    // 链接经过百分号编码，其中不会出现 dbus-send 用作数组分隔符的逗号
+    let uri = format!("array:string:{}", crate::file_uri::from_path(path)); 
+    let mut c = Command::new("dbus-send"); 
+    c.args([
+        "--session", "--dest=org.freedesktop.FileManager1", "--type=method_call", 
+        "/org/freedesktop/FileManager1", "org.freedesktop.FileManager1.ShowItems", 
+    ]).arg(uri).arg("string:"); 
+    c
+}
@@ -0,0 +1,57 @@
+//! 以文件的形式复制到剪贴板（粘贴到文件管理器或聊天软件时得到文件本身）。
+//! arboard 不支持文件列表，这里调用各平台的命令行工具
+
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// 把文件放到剪贴板
+pub fn copy_file(path: &Path) -> io::Result<()> {
+    let path = crate::file_uri::absolute(path)?; 
+    let status = platform_copy(&path)?; 
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("clipboard command failed: {}", status)))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_copy(path: &Path) -> io::Result<std::process::ExitStatus> {
+    Command::new("powershell")
+        .args(["-NoProfile", "-Command", "Set-Clipboard -LiteralPath $args[0]"])
+        .arg(path)
+        .status()
+}
+
+/// 路径作为脚本参数传入，不拼接进 AppleScript 源码，无需处理引号转义
+#[cfg(target_os = "macos")]
+fn platform_copy(path: &Path) -> io::Result<std::process::ExitStatus> {
+    Command::new("osascript")
+        .args(["-e", "on run argv", "-e", "set the clipboard to (POSIX file (item 1 of argv))", "-e", "end run"])
+        .arg(path)
+        .status()
+}
+
+/// Wayland 下使用 wl-copy，否则使用 xclip；都以 text/uri-list 提供
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_copy(path: &Path) -> io::Result<std::process::ExitStatus> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let uri = format!("{}\r\n", crate::file_uri::from_path(path)); 
+    let mut command = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut c = Command::new("wl-copy"); 
+        c.args(["--type", "text/uri-list"]); 
+        c
+    } else {
+        let mut c = Command::new("xclip"); 
+        c.args(["-selection", "clipboard", "-t", "text/uri-list"]); 
+        c
+    }; 
+    let mut child = command.stdin(Stdio::piped()).spawn()?; 
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(uri.as_bytes())?; 
+    }
+    child.wait()
+}
@@ -0,0 +1,137 @@
+//! `file://` 链接与本地路径之间的转换（RFC 8089），路径中的每一段都做百分号编码；
+//! 以及交给其它程序的绝对路径
+
+use std::borrow::Cow;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 去掉 Windows `canonicalize` 返回的 `\\?\` 前缀；资源管理器与剪贴板都不接受这种写法
+pub fn strip_verbatim(path: &Path) -> PathBuf {
+    let Some(s) = path.to_str() else {
+        return path.to_path_buf(); 
+    }; 
+    if let Some(rest) = s.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", rest))
+    } else if let Some(rest) = s.strip_prefix(r"\\?\") {
+        PathBuf::from(rest)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// 交给其它程序的绝对路径：`canonicalize` 后去掉 `\\?\` 前缀
+pub fn absolute(path: &Path) -> io::Result<PathBuf> {
+    Ok(strip_verbatim(&path.canonicalize()?))
+}
+
+/// 路径段中无需编码的字节（保留 `:` 以便 Windows 盘符写作 `/C:/`）
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~' | b':')
+}
+
+/// 路径的字节形式，分隔符统一为 `/`，并以 `/` 开头
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Cow::Borrowed(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    let s = strip_verbatim(path).to_string_lossy().replace('\\', "/"); 
+    let s = if s.starts_with('/') { s } else { format!("/{}", s) }; 
+    Cow::Owned(s.into_bytes())
+}
+
+/// 把绝对路径转换为 `file://` 链接
+pub fn from_path(path: &Path) -> String {
+    let mut uri = String::from("file://"); 
+    for &b in path_bytes(path).iter() {
+        if is_unreserved(b) || b == b'/' {
+            uri.push(b as char); 
+        } else {
+            uri.push_str(&format!("%{:02X}", b)); 
+        }
+    }
+    uri
+}
+
+/// 解码百分号编码；`%` 后不是两位十六进制数时返回 None
+fn percent_decode(s: &str) -> Option<Vec<u8>> {
+    let bytes = s.as_bytes(); 
+    let mut out = Vec::with_capacity(bytes.len()); 
+    let mut i = 0; 
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?; 
+            out.push(u8::from_str_radix(hex, 16).ok()?); 
+            i += 3; 
+        } else {
+            out.push(bytes[i]); 
+            i += 1; 
+        }
+    }
+    Some(out)
+}
+
+/// 把 `file:///path` 或 `file://localhost/path` 链接转换为本地路径；其它主机或格式返回 None
+pub fn to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?; 
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest); 
+    if !rest.starts_with('/') {
+        return None; 
+    }
+    let bytes = percent_decode(rest)?; 
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        Some(PathBuf::from(std::ffi::OsString::from_vec(bytes)))
+    }
+    #[cfg(not(unix))]
+    {
+        let s = String::from_utf8(bytes).ok()?; 
+        // `/C:/dir` 去掉开头的 `/`
+        let s = match s.as_bytes() {
+            [b'/', d, b':', ..] if d.is_ascii_alphabetic() => &s[1..], 
+            _ => &s[..], 
+        }; 
+        Some(PathBuf::from(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn encodes_each_segment() {
+        assert_eq!(from_path(Path::new("/tmp/a b/c#1,2%.png")), "file:///tmp/a%20b/c%231%2C2%25.png"); 
+        assert_eq!(from_path(Path::new("/图片/x.png")), "file:///%E5%9B%BE%E7%89%87/x.png"); 
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn decodes_local_uris() {
+        assert_eq!(to_path("file:///tmp/a%20b/c%231.png"), Some(PathBuf::from("/tmp/a b/c#1.png"))); 
+        assert_eq!(to_path("file://localhost/tmp/x.png"), Some(PathBuf::from("/tmp/x.png"))); 
+        assert_eq!(to_path("file:///%E5%9B%BE%E7%89%87/x.png"), Some(PathBuf::from("/图片/x.png"))); 
+        let path = Path::new("/tmp/a b,c%d/图.png"); 
+        assert_eq!(to_path(&from_path(path)).as_deref(), Some(path)); 
+    }
+
+    #[test]
+    fn strips_verbatim_prefix() {
+        assert_eq!(strip_verbatim(Path::new(r"\\?\C:\a b\x.png")), PathBuf::from(r"C:\a b\x.png")); 
+        assert_eq!(strip_verbatim(Path::new(r"\\?\UNC\server\share\x.png")), PathBuf::from(r"\\server\share\x.png")); 
+        assert_eq!(strip_verbatim(Path::new("/tmp/x.png")), PathBuf::from("/tmp/x.png")); 
+    }
+
+    #[test]
+    fn rejects_other_uris() {
+        assert_eq!(to_path("/tmp/x.png"), None); 
+        assert_eq!(to_path("file://server/share/x.png"), None); 
+        assert_eq!(to_path("file:///tmp/%zz.png"), None); 
+        assert_eq!(to_path("file:///tmp/%4"), None); 
+    }
+}
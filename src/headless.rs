@@ -0,0 +1,184 @@
+//! 无界面命令行入口：`image-transfer --headless (--script <name> | --preset <name>) [options]`
+
+use std::ffi::OsString;
+
+use crate::image_format::{OutputFormat, OutputSettings};
+use crate::image_mode::ImageMode;
+use crate::preset::{Presets, PRESET_FILE};
+use crate::config::Config;
+use crate::script_execution::{Executor, ExecuteError};
+use crate::script_option::ScriptOption;
+use crate::script_root;
+
+const USAGE : &str = "\
+usage: image-transfer --headless (--script <name> | --preset <name>) [options]
+
+options:
+    --config <path>     configuration file, applied over ./image-transfer.toml
+    --script <name>     script in ./pyscripts (or ./nativescripts with --native)
+    --preset <name>     start from a preset in ./presets.toml; other options override it
+    --native            run a native script instead of a Python script
+    --python <path>     Python interpreter (default: `python` from config, or ./python)
+    --mode <mode>       none | single | bi (default: by number of inputs)
+    --input <path>      input image, may be repeated
+    --args <string>     extra arguments passed to the script
+    -- <words>...       same as --args, with the remaining words joined by spaces
+    --output <path>     output image, its extension is replaced by the output format
+                        (default: `output` from config)
+    --format <ext>      output format: png | jpg | bmp | tiff, or `input` to follow the
+                        first input's format (default: by output extension)
+    --quality <1-100>   output quality for JPEG (default: `output_quality` from config)
+    --help              show this message";
+
+/// 命令行参数
+#[derive(Debug, Default)]
+struct HeadlessArgs {
+    script: Option<String>, 
+    preset: Option<String>, 
+    native: bool, 
+    python: Option<String>, 
+    mode: Option<ImageMode>, 
+    inputs: Vec<String>, 
+    args: String, 
+    output: Option<String>, 
+    format: Option<OutputFormat>, 
+    follow_input: bool, 
+    quality: Option<u8>, 
+    help: bool, 
+}
+
+fn parse(args: impl Iterator<Item = String>) -> Result<HeadlessArgs, String> {
+    let mut r = HeadlessArgs::default(); 
+    let mut args = args; 
+    while let Some(a) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {}", a)); 
+        match a.as_str() {
+            "--script" => r.script = Some(value()?), 
+            "--preset" => r.preset = Some(value()?), 
+            "--native" => r.native = true, 
+            "--python" => r.python = Some(value()?), 
+            "--mode" => {
+                let m = value()?; 
+                r.mode = Some(ImageMode::from_name(&m).ok_or_else(|| format!("unknown mode: {}", m))?); 
+            }
+            "--input" => r.inputs.push(value()?), 
+            "--args" => r.args = value()?, 
+            // 与 --config 的处理一致：之后的内容都是脚本的额外参数
+            "--" => {
+                r.args = args.by_ref().collect::<Vec<_>>().join(" "); 
+            }
+            "--output" => r.output = Some(value()?), 
+            "--format" => {
+                let f = value()?; 
+                if f == "input" {
+                    r.follow_input = true; 
+                } else {
+                    r.format = Some(OutputFormat::from_extension(&f).filter(OutputFormat::can_encode).ok_or_else(|| format!("unknown format: {}", f))?); 
+                }
+            }
+            "--quality" => {
+                let q = value()?; 
+                r.quality = Some(q.parse().ok().filter(|q| (1..=100).contains(q)).ok_or_else(|| format!("invalid quality: {}", q))?); 
+            }
+            "--help" | "-h" => r.help = true, 
+            _ => return Err(format!("unknown argument: {}", a)), 
+        }
+    }
+    Ok(r)
+}
+
+/// 解析参数并执行脚本，返回进程退出码
+pub fn run(args: impl Iterator<Item = String>, config: &Config) -> i32 {
+    let mut args = match parse(args) {
+        Ok(a) => a, 
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, USAGE); 
+            return 2; 
+        }
+    }; 
+    if args.help {
+        println!("{}", USAGE); 
+        return 0; 
+    }
+    if let Some(ref name) = args.preset {
+        let mut presets = Presets::load(PRESET_FILE); 
+        let preset = match presets.presets.remove(name) {
+            Some(p) => p, 
+            None => {
+                eprintln!("Error: preset not found: {}", name); 
+                return 2; 
+            }
+        }; 
+        args.script = args.script.or(Some(preset.script)); 
+        args.native = args.native || preset.native; 
+        args.python = args.python.or(preset.interpreter); 
+        args.mode = args.mode.or(Some(preset.image_mode)); 
+        if args.args.is_empty() {
+            args.args = preset.extra_arguments; 
+        }
+    }
+    let name = match args.script {
+        Some(ref s) => s, 
+        None => {
+            eprintln!("Error: --script or --preset is required\n\n{}", USAGE); 
+            return 2; 
+        }
+    }; 
+    let script = if args.native {
+        script_root::find(&config.native_script_dir, script_root::NATIVE_SCRIPT_EXTENSION, name)
+    } else {
+        script_root::find(&config.py_script_dir, script_root::PY_SCRIPT_EXTENSION, name)
+    }; 
+    let script = match script {
+        Some(s) => s, 
+        None => {
+            eprintln!("Error: script not found: {}", name); 
+            return 2; 
+        }
+    }; 
+    let mode = args.mode.unwrap_or(match args.inputs.len() {
+        0 => ImageMode::None, 
+        1 => ImageMode::SingleImage, 
+        _ => ImageMode::BiImage, 
+    }); 
+    if args.inputs.len() != mode.image_count() {
+        eprintln!("Error: mode {:?} expects {} input(s), got {}", mode, mode.image_count(), args.inputs.len()); 
+        return 2; 
+    }
+    let output = args.output.unwrap_or_else(|| config.output.clone()); 
+    let mut output_settings = OutputSettings::from_path(&output); 
+    output_settings.quality = args.quality.unwrap_or(config.output_quality); 
+    output_settings.follow_input = args.follow_input || (args.format.is_none() && config.output_follow_input); 
+    if let Some(format) = args.format {
+        output_settings.format = format; 
+    }
+    let images : Vec<OsString> = args.inputs.into_iter().map(OsString::from).collect(); 
+    let output_settings = output_settings.resolve(&images); 
+    let output = output_settings.apply(&output); 
+    let executor = Executor {
+        script_option: if args.native {
+            ScriptOption::DirectExecute
+        } else {
+            ScriptOption::PyExecute(args.python.or_else(|| config.python.clone()).map(OsString::from))
+        }, 
+        script: script.into(), 
+        output, 
+        output_settings, 
+        images, 
+        other_args: args.args, 
+    }; 
+    match executor.execute() {
+        Ok(_) => {
+            println!("{}", executor.output.to_string_lossy()); 
+            0
+        }
+        Err(ExecuteError::Status(s)) => {
+            eprintln!("Error: script exited with {}", s); 
+            s.code().unwrap_or(1)
+        }
+        Err(e) => {
+            eprintln!("Error: {:?}", e); 
+            1
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use image::{DynamicImage, ImageFormat, ImageResult};
+
+use serde::{Deserialize, Serialize};
+
+/// 可以读取的输入图像扩展名（image crate 支持解码的格式；GIF 只取第一帧）
+pub const INPUT_EXTENSIONS : &[&str] = &[
+    "jpg", "jpeg", "png", "webp", "bmp", "tif", "tiff", "gif", "tga", "ico", 
+    "pbm", "pgm", "ppm", "pam", "pnm", "hdr", "exr", 
+]; 
+
+/// 默认输出质量
+pub const DEFAULT_QUALITY : u8 = 90; 
+
+/// 脚本输出格式
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Png, 
+    Jpeg, 
+    WebP, 
+    Bmp, 
+    Tiff, 
+}
+
+impl OutputFormat {
+    pub const ALL : [OutputFormat; 5] = [OutputFormat::Png, OutputFormat::Jpeg, OutputFormat::WebP, OutputFormat::Bmp, OutputFormat::Tiff]; 
+    /// 可以选作输出的格式，即 `can_encode` 的格式
+    pub const ENCODABLE : [OutputFormat; 4] = [OutputFormat::Png, OutputFormat::Jpeg, OutputFormat::Bmp, OutputFormat::Tiff]; 
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png", 
+            OutputFormat::Jpeg => "jpg", 
+            OutputFormat::WebP => "webp", 
+            OutputFormat::Bmp => "bmp", 
+            OutputFormat::Tiff => "tiff", 
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "PNG", 
+            OutputFormat::Jpeg => "JPEG", 
+            OutputFormat::WebP => "WebP", 
+            OutputFormat::Bmp => "BMP", 
+            OutputFormat::Tiff => "TIFF", 
+        }
+    }
+
+    /// 本程序能否写出该格式；image 未启用 webp-encoder，WebP 只能读取
+    pub fn can_encode(&self) -> bool {
+        !matches!(self, OutputFormat::WebP)
+    }
+
+    /// 编码时是否使用质量参数；可写出的格式中只有 JPEG 是有损的
+    pub fn uses_quality(&self) -> bool {
+        matches!(self, OutputFormat::Jpeg)
+    }
+
+    pub fn from_extension(ext: &str) -> Option<OutputFormat> {
+        match ext.to_ascii_lowercase().as_str() {
+            "png" => Some(OutputFormat::Png), 
+            "jpg" | "jpeg" => Some(OutputFormat::Jpeg), 
+            "webp" => Some(OutputFormat::WebP), 
+            "bmp" => Some(OutputFormat::Bmp), 
+            "tif" | "tiff" => Some(OutputFormat::Tiff), 
+            _ => None, 
+        }
+    }
+
+    pub fn from_path(path: impl AsRef<Path>) -> Option<OutputFormat> {
+        path.as_ref().extension().and_then(|e| e.to_str()).and_then(OutputFormat::from_extension)
+    }
+}
+
+/// 传给脚本的输出格式与质量
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct OutputSettings {
+    pub format: OutputFormat, 
+    /// 1 - 100，仅对使用质量参数的格式有效
+    pub quality: u8, 
+    /// 使用第一个输入图像的格式，避免反复有损压缩；没有输入时使用 format
+    #[serde(default)]
+    pub follow_input: bool, 
+}
+
+impl OutputSettings {
+    /// 以路径的扩展名决定格式，无法识别或不能写出时使用无损的 PNG
+    pub fn from_path(path: impl AsRef<Path>) -> OutputSettings {
+        OutputSettings {
+            format: OutputFormat::from_path(path).filter(OutputFormat::can_encode).unwrap_or(OutputFormat::Png), 
+            quality: DEFAULT_QUALITY, 
+            follow_input: false, 
+        }
+    }
+
+    /// 确定本次执行实际使用的格式：跟随输入时取第一个输入的格式，
+    /// 输入格式不能作为输出格式时使用 PNG
+    pub fn resolve(&self, inputs: &[OsString]) -> OutputSettings {
+        if !self.follow_input {
+            return *self; 
+        }
+        let format = match inputs.first() {
+            Some(i) => OutputFormat::from_path(i).filter(OutputFormat::can_encode).unwrap_or(OutputFormat::Png), 
+            None => self.format, 
+        }; 
+        OutputSettings { format, follow_input: false, ..*self }
+    }
+
+    /// 界面上显示的名称
+    pub fn name(&self) -> &'static str {
+        if self.follow_input {
+            "Same as Input"
+        } else {
+            self.format.name()
+        }
+    }
+
+    /// 把路径的扩展名替换为输出格式的扩展名
+    pub fn apply(&self, path: impl AsRef<Path>) -> OsString {
+        path.as_ref().with_extension(self.format.extension()).into_os_string()
+    }
+
+    /// 按格式与质量写入图像；JPEG 不支持透明度，先去掉 alpha 通道
+    pub fn save(&self, image: &DynamicImage, path: impl AsRef<Path>) -> ImageResult<()> {
+        match self.format {
+            OutputFormat::Jpeg => {
+                let writer = BufWriter::new(File::create(path)?); 
+                let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(writer, self.quality.clamp(1, 100)); 
+                encoder.encode_image(&image.to_rgb8())
+            }
+            OutputFormat::Png => image.save_with_format(path, ImageFormat::Png), 
+            OutputFormat::WebP => image.save_with_format(path, ImageFormat::WebP), 
+            OutputFormat::Bmp => image.save_with_format(path, ImageFormat::Bmp), 
+            OutputFormat::Tiff => image.save_with_format(path, ImageFormat::Tiff), 
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use image::{ImageBuffer, Rgba};
+
+    /// 每个测试使用各自的临时目录
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("image-transfer-test-{}-{}", name, std::process::id())); 
+        std::fs::create_dir_all(&dir).unwrap(); 
+        dir
+    }
+
+    fn gradient() -> DynamicImage {
+        DynamicImage::ImageRgba8(ImageBuffer::from_fn(16, 8, |x, y| Rgba([(x * 16) as u8, (y * 32) as u8, 128, 255])))
+    }
+
+    #[test]
+    fn saves_every_encodable_format() {
+        let dir = temp_dir("save"); 
+        let image = gradient(); 
+        for format in OutputFormat::ENCODABLE {
+            let settings = OutputSettings { format, ..OutputSettings::from_path("x.png") }; 
+            let path = dir.join(format!("out.{}", format.extension())); 
+            settings.save(&image, &path).unwrap_or_else(|e| panic!("{:?}: {}", format, e)); 
+            let read = image::open(&path).unwrap().to_rgba8(); 
+            assert_eq!(read.dimensions(), (16, 8), "{:?}", format); 
+            if !format.uses_quality() {
+                assert_eq!(read, image.to_rgba8(), "{:?}", format); 
+            }
+        }
+        std::fs::remove_dir_all(dir).unwrap(); 
+    }
+
+    #[test]
+    fn can_encode_matches_the_encoder() {
+        let dir = temp_dir("encode"); 
+        for format in OutputFormat::ALL {
+            let settings = OutputSettings { format, ..OutputSettings::from_path("x.png") }; 
+            let result = settings.save(&gradient(), dir.join(format!("out.{}", format.extension()))); 
+            assert_eq!(result.is_ok(), format.can_encode(), "{:?}", format); 
+        }
+        std::fs::remove_dir_all(dir).unwrap(); 
+    }
+
+    #[test]
+    fn falls_back_to_png() {
+        assert_eq!(OutputSettings::from_path("a.webp").format, OutputFormat::Png); 
+        assert_eq!(OutputSettings::from_path("a.txt").format, OutputFormat::Png); 
+        assert_eq!(OutputSettings::from_path("a.JPEG").format, OutputFormat::Jpeg); 
+        let follow = OutputSettings { follow_input: true, ..OutputSettings::from_path("a.tiff") }; 
+        assert_eq!(follow.resolve(&["in.webp".into()]).format, OutputFormat::Png); 
+        assert_eq!(follow.resolve(&["in.bmp".into()]).format, OutputFormat::Bmp); 
+        assert_eq!(follow.resolve(&[]).format, OutputFormat::Tiff); 
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use image::{DynamicImage, ImageBuffer, ImageResult, Rgba};
+
+/// 临时文件目录：粘贴的图像、方向校正后的副本
+pub fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join("image-transfer"); 
+    let _ = std::fs::create_dir_all(&dir); 
+    dir
+}
+
+/// 临时目录中带时间戳的新文件名
+pub fn temp_file(prefix: &str, extension: &str) -> PathBuf {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0); 
+    temp_dir().join(format!("{}-{}.{}", prefix, millis, extension))
+}
+
+/// 缩小到最长边不超过 max_size 的预览图；本身足够小时原样返回
+pub fn preview(image: ImageBuffer<Rgba<u8>, Vec<u8>>, max_size: u32) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    let (w, h) = image.dimensions(); 
+    if w <= max_size && h <= max_size {
+        return image; 
+    }
+    let scale = max_size as f64 / w.max(h) as f64; 
+    let (pw, ph) = (((w as f64 * scale).round() as u32).max(1), ((h as f64 * scale).round() as u32).max(1)); 
+    image::imageops::thumbnail(&image, pw, ph)
+}
+
+/// 缩放到指定尺寸；尺寸已相同时原样返回
+pub fn match_size(image: ImageBuffer<Rgba<u8>, Vec<u8>>, size: (u32, u32)) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    if image.dimensions() == size {
+        return image; 
+    }
+    image::imageops::resize(&image, size.0, size.1, image::imageops::FilterType::Triangle)
+}
+
+/// 已读取的图像；输入只保留用于显示的预览，执行时使用文件路径
+pub struct LoadedImage {
+    /// 按 EXIF 方向校正并缩小后的预览图
+    pub preview: ImageBuffer<Rgba<u8>, Vec<u8>>, 
+    /// 原图尺寸（方向校正后）
+    pub size: [u32; 2], 
+    /// 原始文件
+    pub path: String, 
+    /// 传给脚本的文件：原始文件，或方向校正后的副本
+    pub script_path: String, 
+    /// 文件大小（字节）
+    pub file_size: u64, 
+    /// 读取时文件的修改时间
+    pub modified: Option<SystemTime>, 
+    /// 完整的解码结果；脚本输出保留它，复制时不必重新读取可能已被覆盖的文件
+    pub image: Option<Arc<ImageBuffer<Rgba<u8>, Vec<u8>>>>, 
+}
+
+impl LoadedImage {
+    /// 脚本输出等不需要校正的图像；同时在读取线程中取得文件大小与修改时间
+    pub fn new(image: ImageBuffer<Rgba<u8>, Vec<u8>>, path: String, preview_size: u32) -> LoadedImage {
+        let size = [image.width(), image.height()]; 
+        let metadata = std::fs::metadata(&path).ok(); 
+        let file_size = metadata.as_ref().map(|m| m.len()).unwrap_or(0); 
+        let modified = metadata.and_then(|m| m.modified().ok()); 
+        LoadedImage { preview: preview(image, preview_size), size, script_path: path.clone(), path, file_size, modified, image: None }
+    }
+
+    /// 同 new，并保留完整的解码结果
+    pub fn decoded(image: ImageBuffer<Rgba<u8>, Vec<u8>>, path: String, preview_size: u32) -> LoadedImage {
+        let image = Arc::new(image); 
+        let mut loaded = LoadedImage::new(image.as_ref().clone(), path, preview_size); 
+        loaded.image = Some(image); 
+        loaded
+    }
+}
+
+/// 读取 EXIF 方向标记（1 - 8）；没有 EXIF 或没有方向标记时返回 None
+pub fn exif_orientation(path: impl AsRef<Path>) -> Option<u32> {
+    let file = File::open(path).ok()?; 
+    let mut reader = BufReader::new(file); 
+    let exif = exif::Reader::new().read_from_container(&mut reader).ok()?; 
+    let field = exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?; 
+    field.value.get_uint(0).filter(|o| (1..=8).contains(o))
+}
+
+/// 按 EXIF 方向旋转 / 翻转为正向
+pub fn apply_orientation(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(), 
+        3 => image.rotate180(), 
+        4 => image.flipv(), 
+        5 => image.rotate90().fliph(), 
+        6 => image.rotate90(), 
+        7 => image.rotate270().fliph(), 
+        8 => image.rotate270(), 
+        _ => image, 
+    }
+}
+
+/// 读取图像并按 EXIF 方向校正
+pub fn open_oriented(path: &str) -> ImageResult<DynamicImage> {
+    let image = image::open(path)?; 
+    Ok(match exif_orientation(path) {
+        Some(o) => apply_orientation(image, o), 
+        None => image, 
+    })
+}
+
+/// 读取选项
+#[derive(Clone, Copy, Debug)]
+pub struct LoadOptions {
+    /// 为带 EXIF 方向的照片写出校正后的副本传给脚本
+    pub normalize: bool, 
+    /// 预览图的最长边
+    pub preview_size: u32, 
+}
+
+/// 读取图像并按 EXIF 方向校正；options.normalize 为 true 且图像需要校正时，
+/// 写出一份正向的 PNG 副本传给脚本，否则脚本使用原始文件
+pub fn load(path: &str, options: LoadOptions) -> ImageResult<LoadedImage> {
+    let image = image::open(path)?; 
+    let orientation = exif_orientation(path).unwrap_or(1); 
+    if orientation == 1 {
+        return Ok(LoadedImage::new(image.to_rgba8(), path.to_string(), options.preview_size)); 
+    }
+    let image = apply_orientation(image, orientation).to_rgba8(); 
+    let mut script_path = path.to_string(); 
+    if options.normalize {
+        let stem = Path::new(path).file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default(); 
+        let copy = temp_file(&format!("oriented-{}", stem), "png"); 
+        match image.save(&copy) {
+            Ok(()) => script_path = copy.to_string_lossy().into_owned(), 
+            Err(e) => eprintln!("Error: {:?}", e), 
+        }
+    }
+    let mut loaded = LoadedImage::new(image, path.to_string(), options.preview_size); 
+    loaded.script_path = script_path; 
+    Ok(loaded)
+}
@@ -1,6 +1,32 @@
-#[derive(Clone, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum ImageMode {
+    #[serde(rename = "none")]
     None,
+    #[serde(rename = "single")]
     SingleImage, 
+    #[serde(rename = "bi")]
     BiImage, 
-}
\ No newline at end of file
+}
+
+impl ImageMode {
+    /// 该模式需要的输入图像数量
+    pub fn image_count(&self) -> usize {
+        match self {
+            ImageMode::None => 0, 
+            ImageMode::SingleImage => 1, 
+            ImageMode::BiImage => 2, 
+        }
+    }
+
+    /// 从命令行名称解析：none / single / bi
+    pub fn from_name(name: &str) -> Option<ImageMode> {
+        match name {
+            "none" => Some(ImageMode::None), 
+            "single" => Some(ImageMode::SingleImage), 
+            "bi" => Some(ImageMode::BiImage), 
+            _ => None, 
+        }
+    }
+}
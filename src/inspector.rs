@@ -0,0 +1,122 @@
+//! 像素检查：光标下像素在原图中的坐标与 RGBA / 十六进制 / HSV 颜色值
+
+use eframe::egui::{self, Color32, Pos2, Rect, Response, Sense};
+use image::{ImageBuffer, Rgba};
+
+/// 光标下的像素
+#[derive(Clone, Copy, Debug)]
+pub struct PixelInfo {
+    /// 原图坐标
+    pub x: u32, 
+    pub y: u32, 
+    pub rgba: [u8; 4], 
+}
+
+impl PixelInfo {
+    /// #RRGGBB，不透明度不是 255 时为 #RRGGBBAA
+    pub fn hex(&self) -> String {
+        let [r, g, b, a] = self.rgba; 
+        if a == 255 {
+            format!("#{:02X}{:02X}{:02X}", r, g, b)
+        } else {
+            format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+        }
+    }
+
+    /// 色相（0 - 360）、饱和度与明度（0 - 1）
+    pub fn hsv(&self) -> (f32, f32, f32) {
+        let [r, g, b] = [self.rgba[0], self.rgba[1], self.rgba[2]].map(|c| c as f32 / 255.); 
+        let max = r.max(g).max(b); 
+        let min = r.min(g).min(b); 
+        let delta = max - min; 
+        let hue = if delta == 0. {
+            0. 
+        } else if max == r {
+            60. * ((g - b) / delta).rem_euclid(6.)
+        } else if max == g {
+            60. * ((b - r) / delta + 2.)
+        } else {
+            60. * ((r - g) / delta + 4.)
+        }; 
+        let saturation = if max == 0. { 0. } else { delta / max }; 
+        (hue, saturation, max)
+    }
+}
+
+/// 屏幕上 image_rect 显示的 buffer 中，pos 处的像素；坐标换算到尺寸为 size 的原图。
+/// buffer 可以是原图缩小后的预览
+pub fn pixel_at(buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>, size: [u32; 2], image_rect: Rect, pos: Pos2) -> Option<PixelInfo> {
+    if !image_rect.contains(pos) || buffer.width() == 0 || buffer.height() == 0 {
+        return None; 
+    }
+    let u = ((pos.x - image_rect.left()) / image_rect.width()).clamp(0., 1.); 
+    let v = ((pos.y - image_rect.top()) / image_rect.height()).clamp(0., 1.); 
+    let coordinate = |t: f32, n: u32| ((t * n as f32) as u32).min(n.saturating_sub(1)); 
+    let p = buffer.get_pixel(coordinate(u, buffer.width()), coordinate(v, buffer.height())); 
+    Some(PixelInfo { x: coordinate(u, size[0]), y: coordinate(v, size[1]), rgba: p.0 })
+}
+
+/// 颜色读数
+pub fn pixel_ui(ui: &mut egui::Ui, info: &PixelInfo) {
+    let [r, g, b, a] = info.rgba; 
+    let (h, s, v) = info.hsv(); 
+    ui.horizontal(|ui| {
+        let (rect, _) = ui.allocate_exact_size([28., 28.].into(), Sense::hover()); 
+        ui.painter().rect_filled(rect, 2., Color32::from_rgba_unmultiplied(r, g, b, a)); 
+        ui.vertical(|ui| {
+            ui.monospace(format!("({}, {})  {}", info.x, info.y, info.hex())); 
+            ui.monospace(format!("RGBA {} {} {} {}", r, g, b, a)); 
+            ui.monospace(format!("HSV {:.0}° {:.0}% {:.0}%", h, s * 100., v * 100.)); 
+        }); 
+    }); 
+}
+
+/// 悬停时在光标旁显示颜色读数；copy 为 true 且点击时把十六进制颜色复制到剪贴板
+pub fn inspect(ui: &egui::Ui, response: &Response, buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>, size: [u32; 2], image_rect: Rect, copy: bool) -> Option<PixelInfo> {
+    let pos = response.hover_pos()?; 
+    let info = pixel_at(buffer, size, image_rect, pos)?; 
+    if copy && response.clicked() {
+        ui.output_mut(|o| o.copied_text = info.hex()); 
+    }
+    egui::show_tooltip_at_pointer(ui.ctx(), response.id.with("pixel"), |ui| pixel_ui(ui, &info)); 
+    Some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(rgba: [u8; 4]) -> PixelInfo {
+        PixelInfo { x: 0, y: 0, rgba }
+    }
+
+    #[test]
+    fn maps_scaled_rect() {
+        // 400 × 200 的原图缩小为 4 × 2 的预览，显示在 200 × 100 的区域里
+        let buffer = ImageBuffer::from_fn(4, 2, |x, y| Rgba([x as u8, y as u8, 0, 255])); 
+        let rect = Rect::from_min_size(Pos2::new(100., 50.), [200., 100.].into()); 
+        let at = |x: f32, y: f32| pixel_at(&buffer, [400, 200], rect, Pos2::new(x, y)).map(|p| (p.x, p.y, p.rgba)); 
+        assert_eq!(at(100., 50.), Some((0, 0, [0, 0, 0, 255]))); 
+        assert_eq!(at(225., 125.), Some((250, 150, [2, 1, 0, 255]))); 
+        assert_eq!(at(300., 150.), Some((399, 199, [3, 1, 0, 255]))); 
+        assert_eq!(at(99., 50.), None); 
+        assert_eq!(at(200., 151.), None); 
+    }
+
+    #[test]
+    fn hsv_of_primaries() {
+        assert_eq!(info([255, 0, 0, 255]).hsv(), (0., 1., 1.)); 
+        assert_eq!(info([0, 255, 0, 255]).hsv(), (120., 1., 1.)); 
+        assert_eq!(info([0, 0, 255, 255]).hsv(), (240., 1., 1.)); 
+        assert_eq!(info([255, 255, 0, 255]).hsv(), (60., 1., 1.)); 
+        assert_eq!(info([255, 0, 255, 255]).hsv(), (300., 1., 1.)); 
+        assert_eq!(info([0, 0, 0, 255]).hsv(), (0., 0., 0.)); 
+        assert_eq!(info([255, 255, 255, 255]).hsv(), (0., 0., 1.)); 
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(info([255, 128, 0, 255]).hex(), "#FF8000"); 
+        assert_eq!(info([255, 128, 0, 16]).hex(), "#FF800010"); 
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use futures::channel::mpsc::{self, UnboundedReceiver};
+use image::{ImageBuffer, Rgba};
+
+use crate::image_loader;
+use crate::script_execution::Executor;
+
+/// 后台任务进度事件
+pub enum JobEvent {
+    /// 第 n 个执行开始
+    Started(usize), 
+    /// 第 n 个执行完成：输出图像的预览与路径
+    Finished(usize, ImageBuffer<Rgba<u8>, Vec<u8>>, String), 
+    /// 第 n 个执行失败
+    Failed(usize, String), 
+    /// 全部结束
+    Done, 
+}
+
+/// 一组按顺序执行的脚本
+pub struct Job {
+    pub runs: Vec<Executor>, 
+    /// 某一步失败后是否放弃剩余步骤
+    pub stop_on_failure: bool, 
+    /// 结果预览图的最长边
+    pub preview_size: u32, 
+    /// 置位后结束正在执行的脚本并放弃剩余步骤
+    pub cancel: Arc<AtomicBool>, 
+}
+
+impl Job {
+    /// 在新线程中执行，通过通道报告进度
+    pub fn spawn(self) -> UnboundedReceiver<JobEvent> {
+        let (tx, rx) = mpsc::unbounded(); 
+        thread::spawn(move || {
+            for (i, executor) in self.runs.iter().enumerate() {
+                if self.cancel.load(Ordering::Relaxed) {
+                    let _ = tx.unbounded_send(JobEvent::Failed(i, "Cancelled".to_string())); 
+                    continue; 
+                }
+                let _ = tx.unbounded_send(JobEvent::Started(i)); 
+                match executor.execute_cancellable(&self.cancel) {
+                    Ok(image) => {
+                        let preview = image_loader::preview(image, self.preview_size); 
+                        let _ = tx.unbounded_send(JobEvent::Finished(i, preview, executor.output.to_string_lossy().into_owned())); 
+                    }
+                    Err(e) => {
+                        eprintln!("Error: {:?}", e); 
+                        let _ = tx.unbounded_send(JobEvent::Failed(i, format!("{:?}", e))); 
+                        if self.stop_on_failure {
+                            break; 
+                        }
+                    }
+                }
+            }
+            let _ = tx.unbounded_send(JobEvent::Done); 
+        }); 
+        rx
+    }
+}
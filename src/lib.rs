@@ -2,4 +2,52 @@ pub mod script_option;
 
 pub mod script_execution; 
 
-pub mod image_mode; 
\ No newline at end of file
+pub mod image_mode; 
+
+pub mod script_root; 
+
+pub mod provenance; 
+
+pub mod headless; 
+
+pub mod job; 
+
+pub mod pipeline; 
+
+pub mod preset; 
+
+pub mod sweep; 
+
+pub mod config; 
+
+pub mod image_format; 
+
+pub mod image_loader; 
+
+pub mod viewer; 
+
+pub mod compare; 
+
+pub mod metrics; 
+
+pub mod diff_view; 
+
+pub mod color_stats; 
+
+pub mod stats_view; 
+
+pub mod inspector; 
+
+pub mod toast; 
+
+pub mod file_clipboard; 
+
+pub mod file_uri; 
+
+pub mod external; 
+
+pub mod manifest; 
+
+pub mod shortcuts; 
+
+pub mod script_search; 
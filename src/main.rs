@@ -1,32 +1,72 @@
 use std::borrow::Cow;
 use std::env::current_dir;
 use std::ffi::OsString;
-use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use arboard::Clipboard;
 use eframe::App;
-use eframe::egui::{SidePanel, RichText, Button, Layout, Spinner, widgets, TextureOptions, Sense};
+use eframe::egui::{SidePanel, RichText, Button, Layout, Spinner, TextureOptions, Sense};
 use eframe::egui;
 use eframe::epaint::{TextureHandle, ColorImage};
-use futures::channel::mpsc::{Receiver, Sender};
+use futures::channel::mpsc::{Receiver, Sender, UnboundedReceiver};
 use futures::channel::oneshot;
 use image::{ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+use image_transfer::headless;
+use image_transfer::image_format::{OutputFormat, OutputSettings};
+use image_transfer::image_loader::{self, LoadOptions, LoadedImage};
 use image_transfer::image_mode::ImageMode;
+use image_transfer::job::{Job, JobEvent};
+use image_transfer::pipeline::{Pipeline, PipelineStep, PIPELINE_OUTPUT_DIR};
+use image_transfer::preset::{Preset, Presets, PRESET_FILE};
+use image_transfer::sweep::{self, Sweep, SWEEP_OUTPUT_DIR, SWEEP_PLACEHOLDER};
+use image_transfer::config::{self, Config};
+use image_transfer::script_execution::{ExecuteError, Executor};
+use image_transfer::script_option::ScriptOption;
+use image_transfer::script_root;
+use image_transfer::viewer::Viewer;
+use image_transfer::compare::{self, Compare};
+use image_transfer::diff_view::DiffView;
+use image_transfer::stats_view::StatsView;
+use image_transfer::inspector;
+use image_transfer::provenance::Provenance;
+use image_transfer::toast::{Toasts, ToastSender};
+use image_transfer::file_clipboard;
+use image_transfer::file_uri;
+use image_transfer::external;
+use image_transfer::manifest::{Manifest, ManifestCache, Manifests};
+use image_transfer::shortcuts::{Action, Shortcuts};
+use image_transfer::script_search;
 
-const PY_SCRIPT_FLUSH_TIME : Duration = Duration::from_secs(1); 
-const NORMAL_SCRIPT_FLUSH_TIME : Duration = Duration::from_secs(1); 
-const TIME_SLICE : Duration = Duration::from_millis(100); 
 
 pub fn main() {
+    let (mut args, config_path) = match config::take_config_arg(std::env::args().skip(1).collect()) {
+        Ok(r) => r, 
+        Err(e) => {
+            eprintln!("Error: {}", e); 
+            std::process::exit(2); 
+        }
+    }; 
+    let (config, problems) = Config::load(config_path.as_deref()); 
+    for p in problems.iter() {
+        eprintln!("Config: {}", p); 
+    }
+    if let Some(n) = config::find_option(&args, "--headless") {
+        args.remove(n); 
+        std::process::exit(headless::run(args.into_iter(), &config)); 
+    }
     println!("Hello, world!"); 
     // Python scripts checking 
     let (py_script_updates_tx, py_script_updates_rx) = futures::channel::mpsc::channel(1); 
     let (py_script_checker_tx, py_script_checker_rx) = futures::channel::mpsc::channel(1); 
+    let (py_root, py_flush_time, time_slice) = (config.py_script_dir.clone(), config.py_script_flush_time(), config.time_slice()); 
     std::thread::spawn(move || {
         let mut py_script_updates_tx = py_script_updates_tx; 
         let mut py_script_checker_rx = py_script_checker_rx; 
+        let mut manifest_cache = ManifestCache::default(); 
         let mut clock : Duration = Duration::from_secs(0); 
         let mut is_flush; 
         loop {
@@ -38,38 +78,27 @@ pub fn main() {
                 Ok(None) => break, 
                 _ => (),
             } 
-            clock += TIME_SLICE; 
-            if clock >= PY_SCRIPT_FLUSH_TIME || is_flush {
+            clock += time_slice; 
+            if clock >= py_flush_time || is_flush {
                 clock = Duration::from_secs(0); 
             } else {
-                std::thread::sleep(TIME_SLICE); 
+                std::thread::sleep(time_slice); 
                 continue; 
             } 
-            let read_dir = std::fs::read_dir("./pyscripts"); 
-            let s; 
-            match read_dir {
-                Ok(dir) => {
-                    let v : Vec<_> = 
-                        dir.into_iter().flat_map(|f| f.ok().map(|f| f.path())).filter(|f| f.extension() == Some("py".as_ref()))
-                            .map(OsString::from)
-                            .map(|f| f.to_string_lossy().into_owned())
-                            .collect(); 
-                    s = py_script_updates_tx.try_send(v); 
-                }
-                Err(_) => {
-                    s = py_script_updates_tx.try_send(Vec::new()); 
-                }
-            }
-            let _ = s; 
+            let v = script_root::scan(&py_root, script_root::PY_SCRIPT_EXTENSION); 
+            let manifests = manifest_cache.load_all(&v); 
+            let _ = py_script_updates_tx.try_send((v, manifests)); 
         }
         eprintln!("python scripts checking thread exit.");
     }); 
     // Native scripts checking 
     let (native_script_updates_tx, native_script_updates_rx) = futures::channel::mpsc::channel(1); 
     let (native_script_checker_tx, native_script_checker_rx) = futures::channel::mpsc::channel(1); 
+    let (native_root, native_flush_time) = (config.native_script_dir.clone(), config.native_script_flush_time()); 
     std::thread::spawn(move || {
         let mut native_script_updates_tx = native_script_updates_tx; 
         let mut native_script_checker_rx = native_script_checker_rx; 
+        let mut manifest_cache = ManifestCache::default(); 
         let mut clock : Duration = Duration::from_secs(0); 
         let mut is_flush; 
         loop {
@@ -81,28 +110,16 @@ pub fn main() {
                 Err(_) => break, 
                 _ => (),
             } 
-            clock += TIME_SLICE; 
-            if clock >= NORMAL_SCRIPT_FLUSH_TIME || is_flush {
+            clock += time_slice; 
+            if clock >= native_flush_time || is_flush {
                 clock = Duration::from_secs(0); 
             } else {
-                std::thread::sleep(TIME_SLICE); 
+                std::thread::sleep(time_slice); 
                 continue; 
             } 
-            let read_dir = std::fs::read_dir("./nativescripts"); 
-            let s; 
-            match read_dir {
-                Ok(dir) => {
-                    let v : Vec<_> = 
-                        dir.into_iter().flat_map(|f| f.ok().map(|f| f.path())).filter(|f| f.extension() == Some("rs".as_ref()))
-                            .map(OsString::from)
-                            .map(|f| f.to_string_lossy().into_owned())
-                            .collect(); 
-                    s = native_script_updates_tx.try_send(v); 
-                }
-                Err(_) => {
-                    s = native_script_updates_tx.try_send(Vec::new()); 
-                }
-            }
+            let v = script_root::scan(&native_root, script_root::NATIVE_SCRIPT_EXTENSION); 
+            let manifests = manifest_cache.load_all(&v); 
+            let s = native_script_updates_tx.try_send((v, manifests)); 
             if s.is_err() {
                 break; 
             }
@@ -117,6 +134,8 @@ pub fn main() {
         is_native_mode: false,
         py_lists: Vec::new(), 
         native_lists: Vec::new(),
+        py_manifests: Manifests::new(), 
+        native_manifests: Manifests::new(), 
         image_mode: ImageMode::BiImage,
         input_image_single: None,
         input_image_bi: (None, None), 
@@ -129,19 +148,57 @@ pub fn main() {
         input_image_bi1_rx: None, 
         input_image_bi2_rx: None, 
         output_image_bi_rx: None,
-        movable_image_display: false,
         extra_arguments: String::new(), 
+        pipeline: Pipeline::default(), 
+        pipeline_window: false, 
+        pipeline_job_rx: None, 
+        pipeline_results: Vec::new(), 
+        presets: Presets::load(PRESET_FILE), 
+        preset_name: String::new(), 
+        sweep: Sweep::default(), 
+        sweep_values: String::new(), 
+        sweep_window: false, 
+        sweep_job_rx: None, 
+        sweep_results: Vec::new(), 
+        config_problems: problems, 
+        output_settings: config.output_settings(), 
+        exif_pass_original: false, 
+        save_provenance: true, 
+        input_slot_rects: Vec::new(), 
+        focused_slot: None, 
+        viewer: None, 
+        compare: None, 
+        diff_view: None, 
+        stats_view: None, 
+        toasts: Toasts::default(), 
+        shortcuts: config.shortcuts(), 
+        shortcut_help: false, 
+        cancel_flags: Vec::new(), 
+        result_copies: Vec::new(), 
+        script_query: String::new(), 
+        script_panel_active: false, 
+        context_menu_open: false, 
+        keyboard_captured: false, 
+        favorite_scripts: Vec::new(), 
+        recent_scripts: Vec::new(), 
+        config, 
     }; 
     let mut native_options = eframe::NativeOptions::default(); 
-    native_options.initial_window_size = Some(egui::Vec2::new(1024.0, 768.0)); 
-    eframe::run_native("Image Transfer", native_options, Box::new( |_| Box::new(app))).unwrap(); 
+    native_options.initial_window_size = Some(app.config.window_size.into()); 
+    eframe::run_native("Image Transfer", native_options, Box::new( |cc| {
+        let mut app = app; 
+        if let Some(state) = cc.storage.and_then(|s| eframe::get_value::<PersistedState>(s, eframe::APP_KEY)) {
+            app.restore(state); 
+        }
+        Box::new(app)
+    })).unwrap(); 
 }
 
 pub struct MyApp {
     /// Python 脚本更新线程
-    pub py_script_updates: (Receiver<Vec<String>>, Sender<()>), 
+    pub py_script_updates: (Receiver<(Vec<String>, Manifests)>, Sender<()>), 
     /// Native 脚本更新线程 
-    pub native_script_updates: (Receiver<Vec<String>>, Sender<()>), 
+    pub native_script_updates: (Receiver<(Vec<String>, Manifests)>, Sender<()>), 
     /// 当前激活的 Python 脚本 
     pub active_py_script: Option<String>, 
     /// 当前激活的 Native 脚本 
@@ -154,188 +211,1456 @@ pub struct MyApp {
     pub py_lists: Vec<String>, 
     /// 当前 native 脚本列表
     pub native_lists: Vec<String>, 
+    /// Python 脚本的清单，随每次扫描更新
+    pub py_manifests: Manifests, 
+    /// Native 脚本的清单，随每次扫描更新
+    pub native_manifests: Manifests, 
     /// 当前图像模式
     pub image_mode: ImageMode, 
     /// 当前的输入图像 
-    pub input_image_single: Option<(TextureHandle, String)>, 
+    pub input_image_single: Option<SlotImage>, 
     /// 当前的输出图像 模式 2 
-    pub input_image_bi: (Option<(TextureHandle, String)>, Option<(TextureHandle, String)>), 
+    pub input_image_bi: (Option<SlotImage>, Option<SlotImage>), 
     /// 当前的输出图像 None 
-    pub output_image_none: Option<(TextureHandle, String)>, 
+    pub output_image_none: Option<SlotImage>, 
     /// 当前的输出图像 模式 1 
-    pub output_image_single: Option<(TextureHandle, String)>, 
+    pub output_image_single: Option<SlotImage>, 
     /// 当前的输出图像 模式 2 
-    pub output_image_bi: Option<(TextureHandle, String)>, 
+    pub output_image_bi: Option<SlotImage>, 
     /// single 模式输入图像通道 
-    pub input_image_singal_rx: Option<oneshot::Receiver<(ImageBuffer<Rgba<u8>, Vec<u8>>, String)>>, 
+    pub input_image_singal_rx: Option<ImageRx>, 
     /// single 模式输出图像通道
-    pub output_image_singal_rx: Option<oneshot::Receiver<(ImageBuffer<Rgba<u8>, Vec<u8>>, String)>>, 
+    pub output_image_singal_rx: Option<ImageRx>, 
     /// None 模式输出图像通道
-    pub output_image_none_rx: Option<oneshot::Receiver<(ImageBuffer<Rgba<u8>, Vec<u8>>, String)>>, 
+    pub output_image_none_rx: Option<ImageRx>, 
     /// bi 模式输入图像通道 1 
-    pub input_image_bi1_rx: Option<oneshot::Receiver<(ImageBuffer<Rgba<u8>, Vec<u8>>, String)>>, 
+    pub input_image_bi1_rx: Option<ImageRx>, 
     /// bi 模式输入图像通道 2 
-    pub input_image_bi2_rx: Option<oneshot::Receiver<(ImageBuffer<Rgba<u8>, Vec<u8>>, String)>>, 
+    pub input_image_bi2_rx: Option<ImageRx>, 
     /// bi 模式输出图像通道 
-    pub output_image_bi_rx: Option<oneshot::Receiver<(ImageBuffer<Rgba<u8>, Vec<u8>>, String)>>, 
-    /// 可移除已经装载的任务
-    pub movable_image_display: bool, 
+    pub output_image_bi_rx: Option<ImageRx>, 
     /// 额外参数
     pub extra_arguments: String, 
+    /// 脚本流水线
+    pub pipeline: Pipeline, 
+    /// 流水线窗口是否打开
+    pub pipeline_window: bool, 
+    /// 流水线任务进度通道
+    pub pipeline_job_rx: Option<UnboundedReceiver<JobEvent>>, 
+    /// 流水线每一步的状态
+    pub pipeline_results: Vec<StepState>, 
+    /// 已保存的预设
+    pub presets: Presets, 
+    /// 预设名称输入框
+    pub preset_name: String, 
+    /// 参数扫描
+    pub sweep: Sweep, 
+    /// 参数扫描取值输入框
+    pub sweep_values: String, 
+    /// 参数扫描窗口是否打开
+    pub sweep_window: bool, 
+    /// 参数扫描任务进度通道
+    pub sweep_job_rx: Option<UnboundedReceiver<JobEvent>>, 
+    /// 参数扫描每次执行的状态
+    pub sweep_results: Vec<StepState>, 
+    /// 应用配置
+    pub config: Config, 
+    /// 启动时读取配置发现的问题；关闭提示后清空
+    pub config_problems: Vec<String>, 
+    /// 输出格式与质量
+    pub output_settings: OutputSettings, 
+    /// 把原始文件传给脚本；否则带 EXIF 方向的照片以校正后的副本传给脚本
+    pub exif_pass_original: bool, 
+    /// 另存结果时一并保存来源记录
+    pub save_provenance: bool, 
+    /// 本帧绘制的输入槽位区域，用于拖放定位
+    pub input_slot_rects: Vec<(Slot, egui::Rect)>, 
+    /// 最近悬停或点击的输入槽位，粘贴的目标
+    pub focused_slot: Option<Slot>, 
+    /// 缩放平移查看器
+    pub viewer: Option<Viewer>, 
+    /// 输入与结果的对比窗口
+    pub compare: Option<Compare>, 
+    /// 差异与指标窗口
+    pub diff_view: Option<DiffView>, 
+    /// 直方图与颜色统计窗口
+    pub stats_view: Option<StatsView>, 
+    /// 复制、保存等操作的结果提示
+    pub toasts: Toasts, 
+    /// 快捷键
+    pub shortcuts: Shortcuts, 
+    /// 快捷键帮助是否打开
+    pub shortcut_help: bool, 
+    /// 正在进行的执行与任务的取消标记
+    pub cancel_flags: Vec<Arc<AtomicBool>>, 
+    /// 移到输入槽位的结果在临时目录中的副本，清除槽位或退出时删除
+    pub result_copies: Vec<std::path::PathBuf>, 
+    /// 脚本列表的搜索文字
+    pub script_query: String, 
+    /// 本帧脚本列表是否被悬停或搜索框有焦点；上下切换脚本只在此时响应
+    pub script_panel_active: bool, 
+    /// 本帧是否显示了槽位的右键菜单
+    pub context_menu_open: bool, 
+    /// 上一帧是否有文字输入、弹出列表或右键菜单占用键盘；Esc 在帧开始时就会关闭它们，所以要看上一帧
+    pub keyboard_captured: bool, 
+    /// 收藏的脚本，显示在列表最前面
+    pub favorite_scripts: Vec<String>, 
+    /// 最近执行的脚本，最新的在前
+    pub recent_scripts: Vec<String>, 
+}
+
+/// 跨启动保存的界面状态
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistedState {
+    pub active_py_script: Option<String>, 
+    pub active_native_script: Option<String>, 
+    pub py_executor: Option<String>, 
+    pub is_native_mode: bool, 
+    pub image_mode: ImageMode, 
+    pub extra_arguments: String, 
+    /// 已载入的输入图像路径
+    pub input_image_single: Option<String>, 
+    pub input_image_bi: (Option<String>, Option<String>), 
+    /// None 使用配置文件中的默认输出设置
+    pub output_settings: Option<OutputSettings>, 
+    pub exif_pass_original: bool, 
+    pub save_provenance: bool, 
+    pub favorite_scripts: Vec<String>, 
+    pub recent_scripts: Vec<String>, 
+}
+
+impl Default for PersistedState {
+    fn default() -> Self {
+        PersistedState {
+            active_py_script: None, 
+            active_native_script: None, 
+            py_executor: None, 
+            is_native_mode: false, 
+            image_mode: ImageMode::BiImage, 
+            extra_arguments: String::new(), 
+            input_image_single: None, 
+            input_image_bi: (None, None), 
+            output_settings: None, 
+            exif_pass_original: false, 
+            save_provenance: true, 
+            favorite_scripts: Vec::new(), 
+            recent_scripts: Vec::new(), 
+        }
+    }
+}
+
+/// 图像读取通道
+type ImageRx = oneshot::Receiver<LoadedImage>; 
+
+/// 槽位中已载入的图像
+pub struct SlotImage {
+    pub texture: TextureHandle, 
+    /// 原始文件
+    pub path: String, 
+    /// 传给脚本的文件
+    pub script_path: String, 
+    /// 原图尺寸
+    pub size: [u32; 2], 
+    /// 文件大小（字节）
+    pub file_size: u64, 
+    /// 读取时文件的修改时间，用于判断文件是否已被之后的执行覆盖
+    pub modified: Option<SystemTime>, 
+    /// 纹理的像素，用于像素检查
+    pub preview: ImageBuffer<Rgba<u8>, Vec<u8>>, 
+    /// 脚本输出的完整解码结果；复制的就是显示的这一份
+    pub image: Option<Arc<ImageBuffer<Rgba<u8>, Vec<u8>>>>, 
+}
+
+/// 槽位的最小边长
+const MIN_SLOT_SIDE : f32 = 120.; 
+/// 交换按钮的宽度
+const SWAP_BUTTON_WIDTH : f32 = 28.; 
+
+/// 文件大小的可读形式
+fn format_file_size(bytes: u64) -> String {
+    const UNITS : [&str; 4] = ["B", "KB", "MB", "GB"]; 
+    let mut size = bytes as f64; 
+    let mut unit = 0; 
+    while size >= 1024. && unit + 1 < UNITS.len() {
+        size /= 1024.; 
+        unit += 1; 
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// 绘制边长为 side 的方形槽位：图像按原比例显示，下方标注尺寸与文件大小；
+/// 没有图像时显示等待动画。悬停时显示光标下的像素，Alt + 点击复制颜色
+fn slot_widget(ui: &mut egui::Ui, side: f32, image: Option<&SlotImage>) -> egui::Response {
+    ui.vertical(|ui| {
+        let (rect, response) = ui.allocate_exact_size([side, side].into(), Sense::click()); 
+        let visuals = ui.style().interact(&response); 
+        ui.painter().rect(rect, visuals.rounding, ui.visuals().extreme_bg_color, visuals.bg_stroke); 
+        match image {
+            Some(i) => {
+                let inner = compare::letterbox(rect.shrink(2.), i.texture.size_vec2()); 
+                let uv = egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.)); 
+                ui.painter().image(i.texture.id(), inner, uv, egui::Color32::WHITE); 
+                let copy = ui.input(|i| i.modifiers.alt); 
+                inspector::inspect(ui, &response, &i.preview, i.size, inner, copy); 
+                ui.label(RichText::new(format!("{} × {} · {}", i.size[0], i.size[1], format_file_size(i.file_size))).small()); 
+            }
+            None => {
+                ui.put(rect, Spinner::new()); 
+                ui.label(RichText::new(" ").small()); 
+            }
+        }
+        response
+    }).inner
+}
+
+/// 读取图像并发送；失败时丢弃 tx，接收端会看到通道关闭
+fn send_image(tx: oneshot::Sender<LoadedImage>, path: String, options: LoadOptions) {
+    match image_loader::load(&path, options) {
+        Ok(image) => {
+            let _ = tx.send(image); 
+        }
+        Err(e) => {
+            eprintln!("Error: {:?}", e); 
+        }
+    }
+}
+
+/// 在新线程中打开文件选择框并读取选中的图像；取消选择时通道被关闭
+fn pick_image(extensions: Vec<String>, options: LoadOptions) -> ImageRx {
+    let (tx, rx) = oneshot::channel(); 
+    std::thread::spawn(move || {
+        let task = rfd::AsyncFileDialog::new()
+            .set_directory(current_dir().unwrap_or("~".into()))
+            .add_filter("Images", &extensions.iter().map(String::as_str).collect::<Vec<_>>())
+            .pick_files(); 
+        let task = futures::executor::block_on(task); 
+        if let Some(path) = task {
+            if path.len() != 1 {
+                return ; 
+            }
+            if let Some(path) = path.into_iter().next() {
+                send_image(tx, path.path().to_string_lossy().into_owned(), options); 
+            }
+        } 
+    }); 
+    rx
+}
+
+/// 在新线程中读取剪贴板中的图像，写入临时文件以便像选中的文件一样传给脚本；
+/// 剪贴板中是文件路径时直接读取该文件
+fn paste_image(options: LoadOptions) -> ImageRx {
+    let (tx, rx) = oneshot::channel(); 
+    std::thread::spawn(move || {
+        let mut clip = match Clipboard::new() {
+            Ok(c) => c, 
+            Err(e) => {
+                eprintln!("Error: {:?}", e); 
+                return ; 
+            }
+        }; 
+        match clip.get_image() {
+            Ok(data) => {
+                let image = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(data.width as u32, data.height as u32, data.bytes.into_owned()); 
+                let Some(image) = image else {
+                    eprintln!("Error: invalid clipboard image"); 
+                    return ; 
+                }; 
+                let path = image_loader::temp_file("paste", "png"); 
+                if let Err(e) = image.save(&path) {
+                    eprintln!("Error: {:?}", e); 
+                    return ; 
+                }
+                let _ = tx.send(LoadedImage::new(image, path.to_string_lossy().into_owned(), options.preview_size)); 
+            }
+            Err(_) => {
+                // 从文件管理器复制的文件以路径或 file:// 链接（text/uri-list，`#` 开头为注释）的形式出现
+                if let Ok(text) = clip.get_text() {
+                    let line = text.lines().map(str::trim).find(|l| !l.is_empty() && !l.starts_with('#')).unwrap_or_default(); 
+                    let path = if line.starts_with("file://") {
+                        file_uri::to_path(line)
+                    } else {
+                        Some(std::path::PathBuf::from(line))
+                    }; 
+                    if let Some(path) = path.filter(|p| p.is_file()) {
+                        send_image(tx, path.to_string_lossy().into_owned(), options); 
+                        return ; 
+                    }
+                }
+                eprintln!("Error: no image in clipboard"); 
+            }
+        }
+    }); 
+    rx
+}
+
+/// 在新线程中读取指定路径的图像
+fn load_image(path: String, options: LoadOptions) -> ImageRx {
+    let (tx, rx) = oneshot::channel(); 
+    std::thread::spawn(move || send_image(tx, path, options)); 
+    rx
+}
+
+/// 在新线程中打开保存对话框，把 source 按所选格式保存；扩展名无法识别或不能写出时使用 settings 的格式。
+/// current（文件仍是显示的那一份）且格式不变时直接复制，否则从 image（没有时从文件）重新编码。
+/// with_provenance 时一并复制来源记录；文件已被覆盖时不复制，它描述的是之后的那一次执行
+fn save_as(source: String, image: Option<Arc<ImageBuffer<Rgba<u8>, Vec<u8>>>>, current: bool, settings: OutputSettings, with_provenance: bool, toasts: ToastSender) {
+    std::thread::spawn(move || {
+        let source = std::path::PathBuf::from(source); 
+        let mut dialog = rfd::AsyncFileDialog::new()
+            .set_directory(current_dir().unwrap_or("~".into()))
+            .set_file_name(&settings.apply(source.file_name().unwrap_or_default()).to_string_lossy()); 
+        for format in OutputFormat::ENCODABLE {
+            dialog = dialog.add_filter(format.name(), &[format.extension()]); 
+        }
+        let Some(dest) = futures::executor::block_on(dialog.save_file()) else {
+            return ; 
+        }; 
+        let mut dest = dest.path().to_path_buf(); 
+        let settings = match OutputFormat::from_path(&dest).filter(OutputFormat::can_encode) {
+            Some(format) => OutputSettings { format, ..settings }, 
+            None => {
+                dest = settings.apply(&dest).into(); 
+                settings
+            }
+        }; 
+        let result = match image {
+            _ if current && OutputFormat::from_path(&source) == Some(settings.format) => {
+                std::fs::copy(&source, &dest).map(|_| ()).map_err(image::ImageError::IoError)
+            }
+            Some(image) => settings.save(&image::DynamicImage::ImageRgba8((*image).clone()), &dest), 
+            None => image_loader::open_oriented(&source.to_string_lossy()).and_then(|image| settings.save(&image, &dest)), 
+        }; 
+        if let Err(e) = result {
+            toasts.error(format!("Cannot save {}: {}", dest.display(), e)); 
+            return ; 
+        }
+        if with_provenance && current {
+            if let Err(e) = Provenance::copy_sidecar(&source, &dest) {
+                toasts.error(format!("Saved {}, but not its provenance: {}", dest.display(), e)); 
+                return ; 
+            }
+        }
+        toasts.success(format!("Saved {}", dest.display())); 
+    }); 
+}
+
+/// 图像槽位
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Slot {
+    InputSingle, 
+    InputBi1, 
+    InputBi2, 
+    OutputNone, 
+    OutputSingle, 
+    OutputBi, 
+}
+
+impl Slot {
+    pub const ALL : [Slot; 6] = [Slot::InputSingle, Slot::InputBi1, Slot::InputBi2, Slot::OutputNone, Slot::OutputSingle, Slot::OutputBi]; 
+
+    /// 某图像模式下的输出槽位
+    pub fn output(mode: &ImageMode) -> Slot {
+        match mode {
+            ImageMode::None => Slot::OutputNone, 
+            ImageMode::SingleImage => Slot::OutputSingle, 
+            ImageMode::BiImage => Slot::OutputBi, 
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Slot::InputSingle => "Input", 
+            Slot::InputBi1 => "Input 1", 
+            Slot::InputBi2 => "Input 2", 
+            Slot::OutputNone => "Output (No Image)", 
+            Slot::OutputSingle => "Output (Single)", 
+            Slot::OutputBi => "Output (Bi)", 
+        }
+    }
+
+    /// 某图像模式下的输入槽位
+    pub fn inputs(mode: &ImageMode) -> &'static [Slot] {
+        match mode {
+            ImageMode::None => &[], 
+            ImageMode::SingleImage => &[Slot::InputSingle], 
+            ImageMode::BiImage => &[Slot::InputBi1, Slot::InputBi2], 
+        }
+    }
+}
+
+/// 后台任务中一次执行的状态
+pub enum StepState {
+    Pending, 
+    Running, 
+    Done(TextureHandle, String), 
+    Failed(String), 
+    /// 前面的执行失败后放弃
+    Skipped, 
+}
+
+/// 把任务事件应用到各次执行的状态上；任务结束时返回 true
+fn apply_job_event(ctx: &egui::Context, states: &mut [StepState], e: JobEvent) -> bool {
+    // 状态列表与任务对不上时（例如已被清空）丢弃事件
+    match e {
+        JobEvent::Started(n) => {
+            if let Some(state) = states.get_mut(n) {
+                *state = StepState::Running; 
+            }
+        }
+        JobEvent::Finished(n, ib, p) => {
+            if let Some(state) = states.get_mut(n) {
+                let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], &ib); 
+                let tex = ctx.load_texture(p.clone(), ci, TextureOptions::LINEAR); 
+                *state = StepState::Done(tex, p); 
+            }
+        }
+        JobEvent::Failed(n, e) => {
+            if let Some(state) = states.get_mut(n) {
+                *state = StepState::Failed(e); 
+            }
+        }
+        JobEvent::Done => {
+            // 失败后停止的任务不会再报告剩余的执行
+            for state in states.iter_mut().filter(|s| matches!(s, StepState::Pending)) {
+                *state = StepState::Skipped; 
+            }
+            return true; 
+        }
+    }
+    false
+}
+
+/// 在固定大小的格子里显示一次执行的状态
+fn step_state_cell(ui: &mut egui::Ui, state: &StepState, size: f32) {
+    match state {
+        StepState::Pending => {
+            ui.add_sized([size, size], egui::Label::new("Pending")); 
+        }
+        StepState::Running => {
+            ui.add_sized([size, size], Spinner::new()); 
+        }
+        StepState::Done(t, p) => {
+            ui.image(t, [size, size]).on_hover_text(p.as_str()); 
+        }
+        StepState::Failed(e) => {
+            ui.add_sized([size, size], egui::Label::new(RichText::new("Failed").color(egui::Color32::RED))).on_hover_text(e.as_str()); 
+        }
+        StepState::Skipped => {
+            ui.add_sized([size, size], egui::Label::new(RichText::new("Skipped").weak())).on_hover_text("Not run because an earlier step failed"); 
+        }
+    }
+}
+
+impl MyApp {
+    /// 根据当前选择的脚本、模式和输入构造执行器；条件不满足时返回 None
+    pub fn executor(&self) -> Option<Executor> {
+        let step = self.pipeline_step()?; 
+        let images = self.input_images()?; 
+        let output_settings = self.output_settings.resolve(&images); 
+        Some(Executor {
+            script_option: step.script_option, 
+            script: step.script.into(), 
+            output: output_settings.apply(&self.config.output), 
+            output_settings, 
+            images, 
+            other_args: step.other_args, 
+        })
+    }
+
+    /// 当前图像模式下已载入的输入图像路径；缺少图像时返回 None
+    pub fn input_images(&self) -> Option<Vec<OsString>> {
+        Some(match self.image_mode {
+            ImageMode::None => Vec::new(), 
+            ImageMode::SingleImage => vec![self.input_image_single.as_ref()?.script_path.clone().into()], 
+            ImageMode::BiImage => vec![
+                self.input_image_bi.0.as_ref()?.script_path.clone().into(), 
+                self.input_image_bi.1.as_ref()?.script_path.clone().into(), 
+            ], 
+        })
+    }
+
+    /// 以当前选择的脚本、模式和参数构造流水线的一步
+    pub fn pipeline_step(&self) -> Option<PipelineStep> {
+        let (script_option, script) = if self.is_native_mode {
+            (ScriptOption::DirectExecute, self.active_native_script.clone()?)
+        } else {
+            let interpreter = self.py_executor.clone().or_else(|| self.config.python.clone()); 
+            (ScriptOption::PyExecute(interpreter.map(OsString::from)), self.active_py_script.clone()?)
+        }; 
+        Some(PipelineStep {
+            script_option, 
+            script, 
+            image_mode: self.image_mode.clone(), 
+            other_args: self.extra_arguments.clone(), 
+        })
+    }
+
+    /// 按当前读取选项重新读取已载入的输入，使传给脚本的文件与 "Pass Original Files" 一致；
+    /// 正在读取的槽位不受影响
+    fn reload_inputs(&mut self) {
+        let options = self.load_options(); 
+        for slot in [Slot::InputSingle, Slot::InputBi1, Slot::InputBi2] {
+            if self.slot_rx(slot).is_some() {
+                continue; 
+            }
+            if let Some(path) = self.slot_image(slot).as_ref().map(|i| i.path.clone()) {
+                *self.slot_rx(slot) = Some(load_image(path, options)); 
+            }
+        }
+    }
+
+    /// 读取输入图像的选项：EXIF 方向处理与预览大小
+    pub fn load_options(&self) -> LoadOptions {
+        LoadOptions {
+            normalize: !self.exif_pass_original, 
+            preview_size: self.config.preview_size, 
+        }
+    }
+
+    /// 槽位中已载入的图像
+    pub fn slot_image(&self, slot: Slot) -> &Option<SlotImage> {
+        match slot {
+            Slot::InputSingle => &self.input_image_single, 
+            Slot::InputBi1 => &self.input_image_bi.0, 
+            Slot::InputBi2 => &self.input_image_bi.1, 
+            Slot::OutputNone => &self.output_image_none, 
+            Slot::OutputSingle => &self.output_image_single, 
+            Slot::OutputBi => &self.output_image_bi, 
+        }
+    }
+
+    pub fn slot_image_mut(&mut self, slot: Slot) -> &mut Option<SlotImage> {
+        match slot {
+            Slot::InputSingle => &mut self.input_image_single, 
+            Slot::InputBi1 => &mut self.input_image_bi.0, 
+            Slot::InputBi2 => &mut self.input_image_bi.1, 
+            Slot::OutputNone => &mut self.output_image_none, 
+            Slot::OutputSingle => &mut self.output_image_single, 
+            Slot::OutputBi => &mut self.output_image_bi, 
+        }
+    }
+
+    /// 槽位的图像读取通道
+    pub fn slot_rx(&mut self, slot: Slot) -> &mut Option<ImageRx> {
+        match slot {
+            Slot::InputSingle => &mut self.input_image_singal_rx, 
+            Slot::InputBi1 => &mut self.input_image_bi1_rx, 
+            Slot::InputBi2 => &mut self.input_image_bi2_rx, 
+            Slot::OutputNone => &mut self.output_image_none_rx, 
+            Slot::OutputSingle => &mut self.output_image_singal_rx, 
+            Slot::OutputBi => &mut self.output_image_bi_rx, 
+        }
+    }
+
+    /// 检查槽位的读取通道：收到图像时上传纹理；通道关闭时（取消选择或读取失败）
+    /// 保留原来的图像，清空槽位使用右键菜单
+    fn poll_slot(&mut self, ctx: &egui::Context, slot: Slot) {
+        let Some(ref mut rx) = self.slot_rx(slot) else {
+            return ; 
+        }; 
+        match rx.try_recv() {
+            Ok(None) => (), 
+            Ok(Some(loaded)) => {
+                let ib = &loaded.preview; 
+                let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], ib); 
+                let texture = ctx.load_texture(loaded.path.clone(), ci, TextureOptions::LINEAR); 
+                if let Some(ref mut view) = self.stats_view {
+                    view.refresh(slot.name(), &loaded.path); 
+                }
+                *self.slot_image_mut(slot) = Some(SlotImage { texture, path: loaded.path, script_path: loaded.script_path, size: loaded.size, file_size: loaded.file_size, modified: loaded.modified, preview: loaded.preview, image: loaded.image }); 
+            }
+            Err(_) => {
+                *self.slot_rx(slot) = None; 
+            } 
+        } 
+    }
+
+    /// 槽位边长：输入槽位并排、输出槽位在下方，随窗口大小缩放
+    fn slot_side(&self, ui: &egui::Ui) -> f32 {
+        let count = self.image_mode.image_count().max(1) as f32; 
+        let spacing = ui.spacing().item_spacing; 
+        let small = ui.text_style_height(&egui::TextStyle::Small) + spacing.y; 
+        let body = ui.text_style_height(&egui::TextStyle::Body) + spacing.y; 
+        let buttons = ui.spacing().interact_size.y + spacing.y; 
+        let swap = if count > 1. { SWAP_BUTTON_WIDTH + spacing.x } else { 0. }; 
+        let width = (ui.available_width() - swap - spacing.x * (count - 1.)) / count; 
+        let height = (ui.available_height() - body - 2. * small - buttons - 4. * spacing.y) / 2.; 
+        width.min(height).max(MIN_SLOT_SIDE)
+    }
+
+    /// 脚本与当前图像模式需要的输入都已就绪
+    fn can_execute(&self) -> bool {
+        self.active_script().is_some() && Slot::inputs(&self.image_mode).iter().all(|&s| self.slot_image(s).is_some())
+    }
+
+    /// 在后台执行当前脚本，结果送到当前模式的输出槽位
+    fn execute(&mut self, ctx: &egui::Context) {
+        if !self.can_execute() {
+            return ; 
+        }
+        let Some(executor) = self.executor() else {
+            return ; 
+        }; 
+        if let Some(script) = self.active_script().cloned() {
+            script_search::push_recent(&mut self.recent_scripts, &script); 
+        }
+        let (tx, rx) = oneshot::channel(); 
+        let preview_size = self.config.preview_size; 
+        *self.slot_rx(Slot::output(&self.image_mode)) = Some(rx); 
+        let cancel = self.cancel_flag(); 
+        let toasts = self.toasts.sender(ctx); 
+        thread::spawn(move || {
+            match executor.execute_cancellable(&cancel) {
+                Ok(image) => {
+                    let _ = tx.send(LoadedImage::decoded(image, executor.output.to_string_lossy().into_owned(), preview_size)); 
+                },
+                Err(ExecuteError::Cancelled) => {
+                    toasts.success("Execution cancelled"); 
+                },
+                Err(e) => {
+                    eprintln!("Error: {:?}", e);  
+                    toasts.error(format!("Execution failed: {:?}", e)); 
+                },
+            } 
+        });
+    }
+
+    /// 新的取消标记，执行线程持有它的另一份引用
+    fn cancel_flag(&mut self) -> Arc<AtomicBool> {
+        let flag = Arc::new(AtomicBool::new(false)); 
+        self.cancel_flags.push(flag.clone()); 
+        flag
+    }
+
+    /// 取消所有正在进行的执行与任务
+    fn cancel(&mut self) {
+        for flag in self.cancel_flags.drain(..) {
+            flag.store(true, Ordering::Relaxed); 
+        }
+    }
+
+    /// 当前模式的脚本列表
+    fn script_list(&self) -> &Vec<String> {
+        match self.is_native_mode {
+            true => &self.native_lists, 
+            false => &self.py_lists, 
+        }
+    }
+
+    /// 当前模式的脚本清单
+    fn manifests(&self) -> &Manifests {
+        match self.is_native_mode {
+            true => &self.native_manifests, 
+            false => &self.py_manifests, 
+        }
+    }
+
+    /// 脚本列表中显示的顺序：有搜索文字时为按得分排列的匹配项，否则收藏的脚本在前
+    fn visible_scripts(&self) -> Vec<String> {
+        let list = self.script_list(); 
+        if !self.script_query.trim().is_empty() {
+            return script_search::search(&self.script_query, list, |s| self.manifests().get(s)).into_iter().cloned().collect(); 
+        }
+        let (mut favorites, others) : (Vec<String>, Vec<String>) = list.iter().cloned().partition(|s| self.favorite_scripts.contains(s)); 
+        favorites.extend(others); 
+        favorites
+    }
+
+    /// 选择脚本
+    fn select_script(&mut self, script: &str) {
+        let script = Some(script.to_string()); 
+        match self.is_native_mode {
+            true => self.active_native_script = script, 
+            false => self.active_py_script = script, 
+        }
+    }
+
+    /// 脚本列表中的一行：收藏开关与脚本名称，悬停显示清单中的说明
+    fn script_row(&mut self, ui: &mut egui::Ui, script: &str) {
+        ui.horizontal(|ui| {
+            let favorite = self.favorite_scripts.iter().any(|s| s == script); 
+            let star = ui.add(Button::new(if favorite { "★" } else { "☆" }).frame(false))
+                .on_hover_text(if favorite { "Remove from favorites" } else { "Add to favorites" }); 
+            if star.clicked() {
+                if favorite {
+                    self.favorite_scripts.retain(|s| s != script); 
+                } else {
+                    self.favorite_scripts.push(script.to_string()); 
+                }
+            }
+            let selected = self.active_script().map(|s| s == script).unwrap_or(false); 
+            let mut label = ui.selectable_label(selected, script); 
+            if let Some(m) = self.manifests().get(script) {
+                if !m.description.is_empty() || !m.tags.is_empty() {
+                    let tags = m.tags.join(", "); 
+                    label = label.on_hover_text(format!("{}\n{}", m.description, tags).trim().to_string()); 
+                }
+            }
+            if label.clicked() {
+                self.select_script(script); 
+            }
+        }); 
+    }
+
+    /// 在脚本列表中选择上一个（-1）或下一个（1）脚本
+    fn select_adjacent_script(&mut self, delta: isize) {
+        let list = self.visible_scripts(); 
+        if list.is_empty() {
+            return ; 
+        }
+        let current = self.active_script().and_then(|a| list.iter().position(|s| s == a)); 
+        let n = match current {
+            Some(i) => (i as isize + delta).clamp(0, list.len() as isize - 1) as usize, 
+            None if delta < 0 => list.len() - 1, 
+            None => 0, 
+        }; 
+        self.select_script(&list[n]); 
+    }
+
+    /// 立即重新扫描当前模式的脚本目录
+    fn refresh_scripts(&mut self) {
+        if self.is_native_mode {
+            let _ = self.native_script_updates.1.try_send(()); 
+        } else {
+            let _ = self.py_script_updates.1.try_send(()); 
+        } 
+    }
+
+    /// 是否有文字输入、弹出列表或右键菜单正在占用键盘
+    fn keyboard_busy(&self, ctx: &egui::Context) -> bool {
+        self.context_menu_open || ctx.wants_keyboard_input() || ctx.memory(|m| m.any_popup_open())
+    }
+
+    /// 处理快捷键：输入文字时不响应（脚本搜索框中的上下切换除外）；
+    /// 取消只在键盘未被输入框或菜单占用时响应，避免退出编辑、关闭菜单的 Esc 同时取消执行
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let typing = ctx.wants_keyboard_input(); 
+        let captured = self.keyboard_captured || self.keyboard_busy(ctx); 
+        let script_panel = self.script_panel_active; 
+        let enabled = |action: Action| match action {
+            Action::PreviousScript | Action::NextScript => script_panel, 
+            Action::Cancel => !captured, 
+            _ => !typing, 
+        }; 
+        for action in self.shortcuts.pressed(ctx, enabled) {
+            match action {
+                Action::Execute => self.execute(ctx), 
+                Action::Cancel => self.cancel(), 
+                Action::OpenInput1 | Action::OpenInput2 => {
+                    let n = if action == Action::OpenInput1 { 0 } else { 1 }; 
+                    if let Some(&slot) = Slot::inputs(&self.image_mode).get(n) {
+                        *self.slot_rx(slot) = Some(pick_image(self.config.image_extensions.clone(), self.load_options())); 
+                    }
+                }
+                Action::CopyResult => self.copy_image(ctx, Slot::output(&self.image_mode)), 
+                Action::PreviousScript => self.select_adjacent_script(-1), 
+                Action::NextScript => self.select_adjacent_script(1), 
+                Action::ToggleMode => self.is_native_mode = !self.is_native_mode, 
+                Action::RefreshScripts => self.refresh_scripts(), 
+                Action::ShowHelp => self.shortcut_help = !self.shortcut_help, 
+            }
+        }
+    }
+
+    /// 当前选择的脚本
+    fn active_script(&self) -> Option<&String> {
+        match self.is_native_mode {
+            true => self.active_native_script.as_ref(), 
+            false => self.active_py_script.as_ref(), 
+        }
+    }
+
+    /// 当前脚本的清单；脚本尚未出现在扫描结果中时为 None
+    fn active_manifest(&self) -> Option<&Manifest> {
+        self.manifests().get(self.active_script()?)
+    }
+
+    /// 槽位在界面上的名称：输入槽位使用脚本清单中的名称
+    fn slot_label(&self, slot: Slot) -> String {
+        let n = Slot::inputs(&self.image_mode).iter().position(|&s| s == slot); 
+        n.and_then(|n| self.active_manifest()?.input_label(n))
+            .map(str::to_string)
+            .unwrap_or_else(|| slot.name().to_string())
+    }
+
+    /// 在查看器中打开槽位的图像
+    fn open_viewer(&mut self, ctx: &egui::Context, slot: Slot) {
+        if let Some(ref i) = self.slot_image(slot) {
+            let max_side = ctx.input(|i| i.max_texture_side); 
+            self.viewer = Some(Viewer::open(i.path.clone(), max_side)); 
+        }
+    }
+
+    /// 当前模式下第一个输入与结果的文件，用于对比
+    fn compare_paths(&self) -> Option<(String, String)> {
+        let input = Slot::inputs(&self.image_mode).first()?; 
+        let before = self.slot_image(*input).as_ref()?.path.clone(); 
+        let after = self.slot_image(Slot::output(&self.image_mode)).as_ref()?.path.clone(); 
+        Some((before, after))
+    }
+
+    /// 可以比较的图像：各槽位，以及流水线与参数扫描的结果
+    fn image_sources(&self) -> Vec<(String, String)> {
+        let mut sources = Vec::new(); 
+        for slot in Slot::ALL {
+            if let Some(ref i) = self.slot_image(slot) {
+                sources.push((slot.name().to_string(), i.path.clone())); 
+            }
+        }
+        for (n, state) in self.pipeline_results.iter().enumerate() {
+            if let StepState::Done(_, ref p) = state {
+                sources.push((format!("Pipeline Step {}", n + 1), p.clone())); 
+            }
+        }
+        for (n, state) in self.sweep_results.iter().enumerate() {
+            if let StepState::Done(_, ref p) = state {
+                sources.push((format!("Sweep Run {}", n + 1), p.clone())); 
+            }
+        }
+        sources
+    }
+
+    /// 打开当前输入与结果的对比窗口
+    fn open_compare(&mut self, ctx: &egui::Context) {
+        if let Some((before, after)) = self.compare_paths() {
+            let max_side = ctx.input(|i| i.max_texture_side); 
+            self.compare = Some(Compare::open(before, after, max_side)); 
+        }
+    }
+
+    /// 绘制输出槽位：点击时把结果图像复制到剪贴板
+    fn output_slot_ui(&mut self, ui: &mut egui::Ui, slot: Slot, side: f32) {
+        let response = slot_widget(ui, side, self.slot_image(slot).as_ref()); 
+        let click = match self.slot_image(slot) {
+            Some(_) => {
+                let response = response.context_menu(|ui| {
+                    self.context_menu_open = true; 
+                    if ui.button("Save As…").clicked() {
+                        if let Some(ref i) = self.slot_image(slot) {
+                            let settings = OutputSettings { quality: self.output_settings.quality, ..OutputSettings::from_path(&i.path) }; 
+                            save_as(i.path.clone(), i.image.clone(), self.file_current(slot), settings, self.save_provenance, self.toasts.sender(ui.ctx())); 
+                        }
+                        ui.close_menu(); 
+                    }
+                    ui.checkbox(&mut self.save_provenance, "Save Provenance with Result"); 
+                    ui.separator(); 
+                    if ui.button("Copy Image").clicked() {
+                        self.copy_image(ui.ctx(), slot); 
+                        ui.close_menu(); 
+                    }
+                    if ui.button("Copy File Path").clicked() {
+                        self.copy_path(ui.ctx(), slot); 
+                        ui.close_menu(); 
+                    }
+                    let copy_file = ui.add_enabled(self.file_current(slot), Button::new("Copy as File"))
+                        .on_disabled_hover_text("The result file has been replaced by a later execution"); 
+                    if copy_file.clicked() {
+                        self.copy_file(ui.ctx(), slot); 
+                        ui.close_menu(); 
+                    }
+                    ui.separator(); 
+                    self.use_as_input_buttons(ui, slot); 
+                    self.file_menu_items(ui, slot); 
+                    if ui.add_enabled(self.compare_paths().is_some(), Button::new("Compare with Input")).clicked() {
+                        self.open_compare(ui.ctx()); 
+                        ui.close_menu(); 
+                    }
+                    if ui.add_enabled(self.compare_paths().is_some(), Button::new("Difference with Input")).clicked() {
+                        if let Some((a, b)) = self.compare_paths() {
+                            let mut view = DiffView::new(Some(a), Some(b)); 
+                            view.compute(); 
+                            self.diff_view = Some(view); 
+                        }
+                        ui.close_menu(); 
+                    }
+                    ui.separator(); 
+                    if ui.button("Clear").clicked() {
+                        self.clear_slot(slot); 
+                        ui.close_menu(); 
+                    }
+                }); 
+                response.clicked() && !ui.input(|i| i.modifiers.alt)
+            },
+            None => false, 
+        };
+        if click {
+            self.copy_image(ui.ctx(), slot); 
+        }
+    }
+
+    /// 右键菜单中查看文件的几项：查看器、外部程序、文件管理器
+    fn file_menu_items(&mut self, ui: &mut egui::Ui, slot: Slot) {
+        if ui.button("Open in Viewer").clicked() {
+            self.open_viewer(ui.ctx(), slot); 
+            ui.close_menu(); 
+        }
+        let Some(path) = self.slot_image(slot).as_ref().map(|i| i.path.clone()) else {
+            return ; 
+        }; 
+        if ui.button("Open Externally").clicked() {
+            if let Err(e) = external::open(path.as_ref()) {
+                self.toasts.sender(ui.ctx()).error(format!("Cannot open {}: {}", path, e)); 
+            }
+            ui.close_menu(); 
+        }
+        if ui.button("Reveal in File Manager").clicked() {
+            if let Err(e) = external::reveal(path.as_ref()) {
+                self.toasts.sender(ui.ctx()).error(format!("Cannot reveal {}: {}", path, e)); 
+            }
+            ui.close_menu(); 
+        }
+    }
+
+    /// 槽位的文件是否仍是读取时的那一份（修改时间未变）
+    fn file_current(&self, slot: Slot) -> bool {
+        let Some(ref i) = self.slot_image(slot) else {
+            return false; 
+        }; 
+        let modified = std::fs::metadata(&i.path).and_then(|m| m.modified()).ok(); 
+        modified.is_some() && modified == i.modified
+    }
+
+    /// 清空槽位，并放弃正在进行的读取
+    fn clear_slot(&mut self, slot: Slot) {
+        if let Some(image) = self.slot_image_mut(slot).take() {
+            let path = std::path::PathBuf::from(image.path); 
+            if let Some(n) = self.result_copies.iter().position(|p| *p == path) {
+                let _ = std::fs::remove_file(self.result_copies.remove(n)); 
+            }
+        }
+        *self.slot_rx(slot) = None; 
+    }
+
+    /// 交换双图模式的两个输入（内容图与风格图）
+    fn swap_inputs(&mut self) {
+        std::mem::swap(&mut self.input_image_bi.0, &mut self.input_image_bi.1); 
+        std::mem::swap(&mut self.input_image_bi1_rx, &mut self.input_image_bi2_rx); 
+    }
+
+    /// 把结果连同纹理移到输入槽位，不重新解码；文件先复制到临时目录，下一次执行不会覆盖它
+    fn use_output_as_input(&mut self, ctx: &egui::Context, slot: Slot, input: Slot) {
+        let Some(mut image) = self.slot_image_mut(slot).take() else {
+            return ; 
+        }; 
+        let source = std::path::PathBuf::from(&image.path); 
+        let extension = source.extension().map(|e| e.to_string_lossy().into_owned()).unwrap_or_else(|| "png".to_string()); 
+        let copy = image_loader::temp_file("result", &extension); 
+        match std::fs::copy(&source, &copy) {
+            Ok(_) => {
+                image.path = copy.to_string_lossy().into_owned(); 
+                image.script_path = image.path.clone(); 
+                // 输入槽位与其它来源一样从文件读取，不再保留解码后的整幅图像
+                image.image = None; 
+                self.result_copies.push(copy); 
+                if let Some(ref mut view) = self.stats_view {
+                    view.refresh(input.name(), &image.path); 
+                }
+                *self.slot_rx(input) = None; 
+                *self.slot_image_mut(input) = Some(image); 
+            }
+            Err(e) => {
+                self.toasts.sender(ctx).error(format!("Cannot copy {}: {}", source.display(), e)); 
+                *self.slot_image_mut(slot) = Some(image); 
+            }
+        }
+    }
+
+    /// 把结果移到某个输入槽位的按钮，每个输入槽位一个
+    fn use_as_input_buttons(&mut self, ui: &mut egui::Ui, slot: Slot) {
+        for &input in Slot::inputs(&self.image_mode) {
+            if ui.button(format!("Use as {}", self.slot_label(input))).clicked() {
+                self.use_output_as_input(ui.ctx(), slot, input); 
+                ui.close_menu(); 
+            }
+        }
+    }
+
+    /// 把槽位显示的图像复制到剪贴板；脚本输出使用保留的解码结果，其余的从文件读取
+    fn copy_image(&self, ctx: &egui::Context, slot: Slot) {
+        let Some(ref i) = self.slot_image(slot) else {
+            return ; 
+        }; 
+        let (image, path) = (i.image.clone(), i.path.clone()); 
+        let toasts = self.toasts.sender(ctx); 
+        thread::spawn(move || {
+            let image = match image {
+                Some(image) => image, 
+                None => match image_loader::open_oriented(&path) {
+                    Ok(image) => Arc::new(image.to_rgba8()), 
+                    Err(e) => {
+                        toasts.error(format!("Cannot read {}: {}", path, e)); 
+                        return ; 
+                    }
+                }, 
+            }; 
+            let result = Clipboard::new().and_then(|mut clip| clip.set_image(arboard::ImageData { 
+                width: image.width() as usize, 
+                height: image.height() as usize, 
+                bytes: Cow::Borrowed(image.as_raw()), 
+            })); 
+            toasts.result(result.map(|_| format!("Copied {} × {} image", image.width(), image.height()))); 
+        }); 
+    }
+
+    /// 把槽位图像的文件路径复制到剪贴板
+    fn copy_path(&self, ctx: &egui::Context, slot: Slot) {
+        let Some(ref i) = self.slot_image(slot) else {
+            return ; 
+        }; 
+        let path = file_uri::absolute(i.path.as_ref()).map(|p| p.to_string_lossy().into_owned()).unwrap_or_else(|_| i.path.clone()); 
+        ctx.output_mut(|o| o.copied_text = path.clone()); 
+        self.toasts.sender(ctx).success(format!("Copied {}", path)); 
+    }
+
+    /// 把槽位图像的文件以文件的形式复制到剪贴板
+    fn copy_file(&self, ctx: &egui::Context, slot: Slot) {
+        let Some(ref i) = self.slot_image(slot) else {
+            return ; 
+        }; 
+        let path = i.path.clone(); 
+        let toasts = self.toasts.sender(ctx); 
+        thread::spawn(move || {
+            let result = file_clipboard::copy_file(path.as_ref()); 
+            toasts.result(result.map(|_| format!("Copied file {}", path))); 
+        }); 
+    }
+
+    /// 绘制输入槽位：已载入时显示图像，否则显示等待动画；点击打开文件选择框，
+    /// 拖入文件时高亮
+    fn input_slot_ui(&mut self, ui: &mut egui::Ui, slot: Slot, side: f32) {
+        let response = ui.vertical(|ui| {
+            ui.label(RichText::new(self.slot_label(slot)).strong()); 
+            slot_widget(ui, side, self.slot_image(slot).as_ref())
+        }).inner; 
+        let response = if self.slot_image(slot).is_none() {
+            response.on_hover_text("Click to open an image, Ctrl+V to paste one")
+        } else {
+            response
+        }; 
+        let alt = ui.input(|i| i.modifiers.alt); 
+        if response.clicked() && !alt {
+            *self.slot_rx(slot) = Some(pick_image(self.config.image_extensions.clone(), self.load_options())); 
+        }
+        if response.hovered() || response.clicked() {
+            self.focused_slot = Some(slot); 
+        }
+        let response = response.context_menu(|ui| {
+            self.context_menu_open = true; 
+            let loaded = self.slot_image(slot).is_some(); 
+            if ui.button(if loaded { "Replace…" } else { "Open…" }).clicked() {
+                *self.slot_rx(slot) = Some(pick_image(self.config.image_extensions.clone(), self.load_options())); 
+                ui.close_menu(); 
+            }
+            if ui.button("Paste Image").clicked() {
+                *self.slot_rx(slot) = Some(paste_image(self.load_options())); 
+                ui.close_menu(); 
+            }
+            if ui.add_enabled(loaded, Button::new("Clear")).clicked() {
+                self.clear_slot(slot); 
+                ui.close_menu(); 
+            }
+            let swap = format!("Swap {} and {}", self.slot_label(Slot::InputBi1), self.slot_label(Slot::InputBi2)); 
+            if self.image_mode == ImageMode::BiImage && ui.button(swap).clicked() {
+                self.swap_inputs(); 
+                ui.close_menu(); 
+            }
+            if loaded {
+                ui.separator(); 
+                self.file_menu_items(ui, slot); 
+                if ui.button("Copy File Path").clicked() {
+                    self.copy_path(ui.ctx(), slot); 
+                    ui.close_menu(); 
+                }
+            }
+        }); 
+        if self.focused_slot == Some(slot) {
+            ui.painter().rect_stroke(response.rect.expand(2.), 4., egui::Stroke::new(1., ui.visuals().selection.bg_fill)); 
+        }
+        let dragging = ui.ctx().input(|i| !i.raw.hovered_files.is_empty()); 
+        if dragging {
+            let hovered = ui.ctx().input(|i| i.pointer.hover_pos()).map(|p| response.rect.contains(p)).unwrap_or(false); 
+            let stroke = if hovered {
+                egui::Stroke::new(3., ui.visuals().selection.stroke.color)
+            } else {
+                egui::Stroke::new(1., ui.visuals().weak_text_color())
+            }; 
+            ui.painter().rect_stroke(response.rect, 4., stroke); 
+        }
+        self.input_slot_rects.push((slot, response.rect)); 
+    }
+
+    /// 把拖入的文件载入到光标下的输入槽位；光标不在任何槽位上时依次填入当前模式的槽位
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let (dropped, pos) = ctx.input(|i| (i.raw.dropped_files.clone(), i.pointer.hover_pos())); 
+        let paths : Vec<String> = dropped.into_iter()
+            .filter_map(|f| f.path)
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect(); 
+        if paths.is_empty() {
+            return ; 
+        }
+        let target = pos.and_then(|p| self.input_slot_rects.iter().find(|(_, r)| r.contains(p)).map(|(s, _)| *s)); 
+        match target {
+            Some(slot) => {
+                *self.slot_rx(slot) = Some(load_image(paths[0].clone(), self.load_options())); 
+            }
+            None => {
+                for (slot, path) in Slot::inputs(&self.image_mode).iter().zip(paths) {
+                    *self.slot_rx(*slot) = Some(load_image(path, self.load_options())); 
+                }
+            }
+        }
+    }
+
+    /// Ctrl+V 粘贴到获得焦点的输入槽位；正在编辑文本时不处理
+    fn handle_paste(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return ; 
+        }
+        if !ctx.input(|i| i.modifiers.command && i.key_pressed(egui::Key::V)) {
+            return ; 
+        }
+        let slot = self.focused_slot.filter(|s| Slot::inputs(&self.image_mode).contains(s))
+            .or_else(|| Slot::inputs(&self.image_mode).first().copied()); 
+        if let Some(slot) = slot {
+            *self.slot_rx(slot) = Some(paste_image(self.load_options())); 
+        }
+    }
+
+    /// 需要跨启动保存的状态
+    /// 保存的输入路径；结果的临时副本在退出时删除，不保存
+    fn persisted_input(&self, image: &Option<SlotImage>) -> Option<String> {
+        let path = &image.as_ref()?.path; 
+        if self.result_copies.iter().any(|p| p.as_path() == std::path::Path::new(path)) {
+            return None; 
+        }
+        Some(path.clone())
+    }
+
+    pub fn persisted_state(&self) -> PersistedState {
+        PersistedState {
+            active_py_script: self.active_py_script.clone(), 
+            active_native_script: self.active_native_script.clone(), 
+            py_executor: self.py_executor.clone(), 
+            is_native_mode: self.is_native_mode, 
+            image_mode: self.image_mode.clone(), 
+            extra_arguments: self.extra_arguments.clone(), 
+            input_image_single: self.persisted_input(&self.input_image_single), 
+            input_image_bi: (
+                self.persisted_input(&self.input_image_bi.0), 
+                self.persisted_input(&self.input_image_bi.1), 
+            ), 
+            // 与配置的默认值相同时不保存，之后修改配置文件仍然生效
+            output_settings: Some(self.output_settings).filter(|s| *s != self.config.output_settings()), 
+            exif_pass_original: self.exif_pass_original, 
+            save_provenance: self.save_provenance, 
+            favorite_scripts: self.favorite_scripts.clone(), 
+            recent_scripts: self.recent_scripts.clone(), 
+        }
+    }
+
+    /// 恢复保存的状态；输入图像在后台重新读取
+    pub fn restore(&mut self, state: PersistedState) {
+        self.active_py_script = state.active_py_script; 
+        self.active_native_script = state.active_native_script; 
+        self.py_executor = state.py_executor; 
+        self.is_native_mode = state.is_native_mode; 
+        self.image_mode = state.image_mode; 
+        self.extra_arguments = state.extra_arguments; 
+        self.output_settings = state.output_settings.unwrap_or_else(|| self.config.output_settings()); 
+        self.input_image_single = None; 
+        self.input_image_bi = (None, None); 
+        self.exif_pass_original = state.exif_pass_original; 
+        self.save_provenance = state.save_provenance; 
+        self.favorite_scripts = state.favorite_scripts; 
+        self.recent_scripts = state.recent_scripts; 
+        let normalize = self.load_options(); 
+        self.input_image_singal_rx = state.input_image_single.map(|p| load_image(p, normalize)); 
+        self.input_image_bi1_rx = state.input_image_bi.0.map(|p| load_image(p, normalize)); 
+        self.input_image_bi2_rx = state.input_image_bi.1.map(|p| load_image(p, normalize)); 
+    }
+
+    /// 应用预设：切换模式、脚本、解释器、图像模式与额外参数
+    pub fn apply_preset(&mut self, preset: &Preset) {
+        self.is_native_mode = preset.native; 
+        if preset.native {
+            self.active_native_script = Some(preset.script.clone()); 
+        } else {
+            self.active_py_script = Some(preset.script.clone()); 
+            self.py_executor = preset.interpreter.clone(); 
+        }
+        self.image_mode = preset.image_mode.clone(); 
+        self.extra_arguments = preset.extra_arguments.clone(); 
+    }
+
+    /// 流水线窗口：编辑步骤、执行并查看每一步的结果
+    fn pipeline_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.pipeline_window; 
+        let running = self.pipeline_job_rx.is_some(); 
+        egui::Window::new("Pipeline").open(&mut open).default_width(420.).show(ctx, |ui| {
+            if self.pipeline.steps.is_empty() {
+                ui.label("No steps. Select a script and press \"Add to Pipeline\"."); 
+            }
+            let mut action = None; 
+            let count = self.pipeline.steps.len(); 
+            for (n, step) in self.pipeline.steps.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}. {} [{:?}] {}", n + 1, step.script, step.image_mode, step.other_args)); 
+                    ui.add_enabled_ui(!running, |ui| {
+                        if ui.add_enabled(n > 0, Button::new("⏶")).clicked() {
+                            action = Some((n, n - 1)); 
+                        }
+                        if ui.add_enabled(n + 1 < count, Button::new("⏷")).clicked() {
+                            action = Some((n, n + 1)); 
+                        }
+                        if ui.button("✖").clicked() {
+                            action = Some((n, usize::MAX)); 
+                        }
+                    }); 
+                }); 
+            }
+            match action {
+                Some((n, usize::MAX)) => {
+                    self.pipeline.steps.remove(n); 
+                    self.pipeline_results.clear(); 
+                }
+                Some((a, b)) => {
+                    self.pipeline.steps.swap(a, b); 
+                    self.pipeline_results.clear(); 
+                }
+                None => (), 
+            }
+            ui.separator(); 
+            ui.horizontal(|ui| {
+                // 预先展开，无法执行时禁用按钮并显示原因
+                let runs = match self.input_images() {
+                    _ if self.pipeline.steps.is_empty() => Err("Add steps first".to_string()), 
+                    Some(inputs) => self.pipeline.executors(&inputs, PIPELINE_OUTPUT_DIR, &self.output_settings), 
+                    None => Err(format!("Load the {} input(s) first", self.image_mode.image_count())), 
+                }; 
+                let run = ui.add_enabled(!running && runs.is_ok(), Button::new("Run Pipeline")); 
+                let error = runs.as_ref().err().filter(|_| !self.pipeline.steps.is_empty()).cloned(); 
+                if run.clicked() {
+                    if let Ok(runs) = runs {
+                        self.pipeline_results = runs.iter().map(|_| StepState::Pending).collect(); 
+                        self.pipeline_job_rx = Some(Job { runs, stop_on_failure: true, preview_size: self.config.preview_size, cancel: self.cancel_flag() }.spawn()); 
+                    }
+                }
+                if ui.add_enabled(!running, Button::new("Clear")).clicked() {
+                    self.pipeline.steps.clear(); 
+                    self.pipeline_results.clear(); 
+                }
+                if running {
+                    ui.add(Spinner::new()); 
+                }
+                if let Some(e) = error {
+                    ui.colored_label(egui::Color32::RED, e); 
+                }
+            }); 
+            egui::ScrollArea::horizontal().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for (n, state) in self.pipeline_results.iter().enumerate() {
+                        ui.vertical(|ui| {
+                            ui.label(format!("Step {}", n + 1)); 
+                            step_state_cell(ui, state, 150.); 
+                        }); 
+                    }
+                }); 
+            }); 
+        }); 
+        self.pipeline_window = open; 
+    }
+
+    /// 参数扫描窗口：按取值列表展开多次执行，并以网格显示结果
+    fn sweep_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.sweep_window; 
+        let running = self.sweep_job_rx.is_some(); 
+        egui::Window::new("Parameter Sweep").open(&mut open).default_width(640.).show(ctx, |ui| {
+            egui::Grid::new("sweep_form").num_columns(2).show(ui, |ui| {
+                ui.label("Argument Template: "); 
+                ui.add(egui::TextEdit::singleline(&mut self.sweep.template).hint_text("--style-weight={}")); 
+                ui.end_row(); 
+                ui.label("Values: "); 
+                ui.add(egui::TextEdit::singleline(&mut self.sweep_values).hint_text("1, 2, 5  or  0..1:0.25")); 
+                ui.end_row(); 
+            }); 
+            let values = sweep::parse_values(&self.sweep_values); 
+            if let Err(ref e) = values {
+                if !self.sweep_values.trim().is_empty() {
+                    ui.colored_label(egui::Color32::RED, e); 
+                }
+            }
+            if !self.sweep.template.contains(SWEEP_PLACEHOLDER) {
+                ui.colored_label(egui::Color32::YELLOW, format!("Template has no \"{}\" placeholder.", SWEEP_PLACEHOLDER)); 
+            }
+            // 模板整体作为一个参数传给脚本，空格不会拆分参数
+            if self.sweep.template.trim().contains(char::is_whitespace) {
+                ui.colored_label(egui::Color32::YELLOW, "The template is passed as a single argument; use \"--name=value\" instead of spaces."); 
+            }
+            ui.horizontal(|ui| {
+                let executor = self.executor(); 
+                let can_run = !running && values.is_ok() && executor.is_some(); 
+                if ui.add_enabled(can_run, Button::new("Run Sweep")).clicked() {
+                    if let (Ok(values), Some(executor)) = (values, executor) {
+                        self.sweep.values = values; 
+                        let runs = self.sweep.executors(&executor, SWEEP_OUTPUT_DIR); 
+                        self.sweep_results = runs.iter().map(|_| StepState::Pending).collect(); 
+                        self.sweep_job_rx = Some(Job { runs, stop_on_failure: false, preview_size: self.config.preview_size, cancel: self.cancel_flag() }.spawn()); 
+                    }
+                }
+                if running {
+                    ui.add(Spinner::new()); 
+                }
+            }); 
+            ui.separator(); 
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("sweep_results").spacing([8., 8.]).show(ui, |ui| {
+                    for (n, state) in self.sweep_results.iter().enumerate() {
+                        ui.vertical(|ui| {
+                            ui.label(self.sweep.values.get(n).map(|v| v.as_str()).unwrap_or_default()); 
+                            step_state_cell(ui, state, 150.); 
+                        }); 
+                        if n % 4 == 3 {
+                            ui.end_row(); 
+                        }
+                    }
+                }); 
+            }); 
+        }); 
+        self.sweep_window = open; 
+    }
 }
 
 impl App for MyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.persisted_state()); 
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        for path in self.result_copies.drain(..) {
+            let _ = std::fs::remove_file(path); 
+        }
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.context_menu_open = false; 
+        // 执行线程结束后只剩这里的引用
+        self.cancel_flags.retain(|f| Arc::strong_count(f) > 1); 
         // 检查 Python 脚本更新 
         match self.py_script_updates.0.try_next() {
-            Ok(Some(v)) => {
+            Ok(Some((v, manifests))) => {
                 self.py_lists = v; 
+                self.py_manifests = manifests; 
             }
             _ => (),
         } 
         // 检查 Native 脚本更新 
         match self.native_script_updates.0.try_next() {
-            Ok(Some(v)) => {
+            Ok(Some((v, manifests))) => {
                 self.native_lists = v; 
+                self.native_manifests = manifests; 
             }
             _ => (),
         } 
-        match self.input_image_singal_rx {
-            Some(ref mut rx) => {
-                match rx.try_recv() {
-                    Ok(None) => (), 
-                    Ok(Some((ib, n))) => {
-                        let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], &ib); 
-                        let tex = ctx.load_texture(n.clone(), ci, TextureOptions::LINEAR); 
-                        self.input_image_single = Some((tex, n)); 
-                    }
-                    Err(_) => {
-                        self.input_image_singal_rx = None; 
-                        if self.movable_image_display {
-                            self.input_image_single = None; 
-                        }
-                    } 
-                } 
-            },
-            None => {},
-        }
-        match self.input_image_bi1_rx {
-            Some(ref mut rx) => {
-                match rx.try_recv() {
-                    Ok(None) => (), 
-                    Ok(Some((ib, n))) => {
-                        let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], &ib); 
-                        let tex = ctx.load_texture(n.clone(), ci, TextureOptions::LINEAR); 
-                        self.input_image_bi.0 = Some((tex, n)); 
-                    }
-                    Err(_) => {
-                        self.input_image_bi1_rx = None; 
-                        if self.movable_image_display {
-                            self.input_image_bi.0 = None; 
-                        }
-                    } 
-                } 
-            },
-            None => {}, 
-        }
-        match self.input_image_bi2_rx {
-            Some(ref mut rx) => {
-                match rx.try_recv() {
-                    Ok(None) => (), 
-                    Ok(Some((ib, n))) => {
-                        let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], &ib); 
-                        let tex = ctx.load_texture(n.clone(), ci, TextureOptions::LINEAR); 
-                        self.input_image_bi.1 = Some((tex, n)); 
-                    }
-                    Err(_) => {
-                        self.input_image_bi2_rx = None; 
-                        if self.movable_image_display {
-                            self.input_image_bi.1 = None; 
-                        }
-                    } 
-                } 
-            },
-            None => {},  
-        }
-        match self.output_image_singal_rx {
-            Some(ref mut rx) => {
-                match rx.try_recv() {
-                    Ok(None) => (),
-                    Ok(Some((ib, n))) => {
-                        let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], &ib); 
-                        let tex = ctx.load_texture(n.clone(), ci, TextureOptions::LINEAR); 
-                        self.output_image_single = Some((tex, n)); 
-                    },
-                    Err(_) => {
-                        self.output_image_singal_rx = None; 
-                        if self.movable_image_display {
-                            self.output_image_single = None; 
-                        } 
-                    },
-                } 
-            },
-            None => {},
-        }
-        match self.output_image_none_rx {
-            Some(ref mut rx) => {
-                match rx.try_recv() {
-                    Ok(None) => (), 
-                    Ok(Some((ib, n))) => {
-                        let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], &ib); 
-                        let tex = ctx.load_texture(n.clone(), ci, TextureOptions::LINEAR); 
-                        self.output_image_none = Some((tex, n)); 
-                    },
-                    Err(_) => {
-                        self.output_image_none_rx = None; 
-                        if self.movable_image_display {
-                            self.output_image_none = None; 
-                        } 
-                    },
-                } 
-            },
-            None => {}, 
-        }
-        match self.output_image_bi_rx {
-            Some(ref mut rx) => {
-                match rx.try_recv() {
-                    Ok(None) => (), 
-                    Ok(Some((ib, n))) => {
-                        let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], &ib); 
-                        let tex = ctx.load_texture(n.clone(), ci, TextureOptions::LINEAR); 
-                        self.output_image_bi = Some((tex, n)); 
-                    },
-                    Err(_) => {
-                        self.output_image_bi_rx = None; 
-                        if self.movable_image_display {
-                            self.output_image_bi = None; 
-                        } 
-                    },
-                } 
-            },
-            None => {},  
+        for slot in Slot::ALL {
+            self.poll_slot(ctx, slot); 
+        }
+        if let Some(ref mut rx) = self.pipeline_job_rx {
+            while let Ok(Some(e)) = rx.try_next() {
+                if apply_job_event(ctx, &mut self.pipeline_results, e) {
+                    self.pipeline_job_rx = None; 
+                    break; 
+                }
+            }
+            ctx.request_repaint_after(self.config.time_slice()); 
+        }
+        if let Some(ref mut rx) = self.sweep_job_rx {
+            while let Ok(Some(e)) = rx.try_next() {
+                if apply_job_event(ctx, &mut self.sweep_results, e) {
+                    self.sweep_job_rx = None; 
+                    break; 
+                }
+            }
+            ctx.request_repaint_after(self.config.time_slice()); 
+        }
+        if !self.config_problems.is_empty() {
+            let mut open = true; 
+            egui::Window::new("Configuration Problems").open(&mut open).show(ctx, |ui| {
+                ui.label("Invalid entries were ignored and defaults used instead: "); 
+                ui.separator(); 
+                for p in self.config_problems.iter() {
+                    ui.label(p); 
+                }
+            }); 
+            if !open {
+                self.config_problems.clear(); 
+            }
+        }
+        self.pipeline_window(ctx); 
+        self.sweep_window(ctx); 
+        if let Some(ref mut viewer) = self.viewer {
+            let mut open = true; 
+            viewer.show(ctx, &mut open); 
+            if !open {
+                self.viewer = None; 
+            }
+        }
+        if let Some(ref mut compare) = self.compare {
+            let mut open = true; 
+            compare.show(ctx, &mut open); 
+            if !open {
+                self.compare = None; 
+            }
+        }
+        if self.diff_view.is_some() {
+            let sources = self.image_sources(); 
+            let mut open = true; 
+            if let Some(ref mut view) = self.diff_view {
+                view.show(ctx, &mut open, &sources); 
+            }
+            if !open {
+                self.diff_view = None; 
+            }
+        }
+        if self.stats_view.is_some() {
+            let sources = self.image_sources(); 
+            let mut open = true; 
+            if let Some(ref mut view) = self.stats_view {
+                view.show(ctx, &mut open, &sources); 
+            }
+            if !open {
+                self.stats_view = None; 
+            }
         }
         SidePanel::left("script_panel").show(ctx, |ui| {
-            let display_python = !self.is_native_mode; 
+            let search = ui.add(egui::TextEdit::singleline(&mut self.script_query).hint_text("Search scripts, tags…").desired_width(f32::INFINITY)); 
+            self.script_panel_active = search.has_focus() || ui.ui_contains_pointer(); 
+            let visible = self.visible_scripts(); 
             egui::ScrollArea::vertical().show(ui, |ui| {
-                if display_python {
-                    for py in self.py_lists.iter() {
-                        let select = self.active_py_script.as_ref().map(|s| s == py).unwrap_or(false); 
-                        let select = ui.selectable_label(select, py); 
-                        if select.clicked() {
-                            self.active_py_script = Some(py.clone());  
+                if self.script_query.trim().is_empty() {
+                    let list = self.script_list(); 
+                    let recent : Vec<String> = self.recent_scripts.iter().filter(|s| list.contains(s)).cloned().collect(); 
+                    if !recent.is_empty() {
+                        ui.label(RichText::new("Recent").small().weak()); 
+                        for script in recent.iter() {
+                            self.script_row(ui, script); 
                         }
+                        ui.separator(); 
                     }
-                } else {
-                    for native in self.native_lists.iter() {
-                        let select = self.active_native_script.as_ref().map(|s| s == native).unwrap_or(false); 
-                        let select = ui.selectable_label(select, native); 
-                        if select.clicked() {
-                            self.active_native_script = Some(native.clone());  
-                        }
-                    }  
+                } else if visible.is_empty() {
+                    ui.label(RichText::new("No matching scripts").weak()); 
+                }
+                for script in visible.iter() {
+                    self.script_row(ui, script); 
                 }
             });
         });
+        let mut reset_layout = false; 
         SidePanel::right("options_panel").min_width(110.).default_width(110.).show(ctx, |ui| {
             let text: RichText; 
             if self.is_native_mode {
@@ -345,20 +1670,18 @@ impl App for MyApp {
             }
             ui.add_space(10.);
             ui.label("Mode: ");
-            let selected = ui.add(egui::Button::new(text).min_size([90.0, 25.0].into())); 
+            let selected = ui.add(egui::Button::new(text).min_size([90.0, 25.0].into()))
+                .on_hover_text(self.shortcuts.text(ctx, Action::ToggleMode)); 
             if selected.clicked() {
                 self.is_native_mode = !self.is_native_mode; 
             }
             ui.separator(); 
             ui.add_space(10.); 
             let flush = ui.add(Button::new("Flush Scripts").min_size([90.0, 25.0].into())
-                .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(45, 45, 0))));
+                .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(45, 45, 0))))
+                .on_hover_text(self.shortcuts.text(ctx, Action::RefreshScripts));
             if flush.clicked() { 
-                if self.is_native_mode {
-                    let _ = self.native_script_updates.1.try_send(()); 
-                } else {
-                    let _ = self.py_script_updates.1.try_send(()); 
-                } 
+                self.refresh_scripts(); 
             } 
             ui.add_space(40.); 
             ui.label("Image Input Mode: "); 
@@ -367,327 +1690,190 @@ impl App for MyApp {
             ui.radio_value(&mut self.image_mode, ImageMode::SingleImage, "Single Image Mode"); 
             ui.radio_value(&mut self.image_mode, ImageMode::BiImage, "Bi-Image Mode");  
             ui.add_space(30.); 
-            ui.horizontal(|ui| {
-                ui.label("Image Unload Allowed: ");
-                let c = ui.selectable_label(self.movable_image_display, "Yes");
-                if c.clicked() {
-                    self.movable_image_display = !self.movable_image_display; 
-                }
-            }); 
+            let pass_original = ui.checkbox(&mut self.exif_pass_original, "Pass Original Files")
+                .on_hover_text("Photos are always shown upright. When off, photos with an EXIF orientation are passed to scripts as an upright copy; when on, the original files are passed unchanged."); 
+            if pass_original.changed() {
+                self.reload_inputs(); 
+            }
             ui.separator();
             ui.add_space(30.); 
-            let mut can_execute: bool; 
-            can_execute = match self.is_native_mode {
-                true => self.active_native_script.is_some(), 
-                false => self.active_py_script.is_some(), 
-            }; 
-            if can_execute {
-                match self.image_mode {
-                    ImageMode::None => (), 
-                    ImageMode::SingleImage => {
-                        can_execute = self.input_image_single.is_some();  
-                    }
-                    ImageMode::BiImage => {
-                        can_execute = self.input_image_bi.0.is_some() && self.input_image_bi.1.is_some(); 
-                    }
-                }
+            let r = ui.add_enabled(self.can_execute(), Button::new("Execute"))
+                .on_hover_text(self.shortcuts.text(ctx, Action::Execute));
+            if r.clicked() {
+                self.execute(ctx); 
             }
-            let r = ui.add_enabled(can_execute, Button::new("Execute"));
-            #[cfg(target_os = "windows")]
-            const DEFAULT_PYTHON_EXECUTOR : &str = "./python.exe"; 
-            #[cfg(not(target_os = "windows"))]
-            const DEFAULT_PYTHON_EXECUTOR : &str = "./python"; 
+            let running = !self.cancel_flags.is_empty(); 
+            let r = ui.add_enabled(running, Button::new("Cancel"))
+                .on_hover_text(self.shortcuts.text(ctx, Action::Cancel)); 
             if r.clicked() {
-                || -> () {
-                    if let Some(ref s) = self.active_py_script {
-                        let mut cmd; 
-                        if self.is_native_mode {
-                            match self.active_native_script {
-                                Some(ref n) => {
-                                    cmd = Command::new(n); 
-                                },
-                                None => {
-                                    return ; 
-                                },
-                            }
-                        } else {
-                            cmd = Command::new(self.py_executor.as_ref().map(|s| s.as_str()).unwrap_or(DEFAULT_PYTHON_EXECUTOR)); 
-                            cmd.arg(s); 
-                        }
-                        cmd.arg("./outcome/result.jpg");
-                        match self.image_mode {
-                            ImageMode::None => (), 
-                            ImageMode::SingleImage => {
-                                if let Some((_, ref n)) = self.input_image_single {
-                                    cmd.arg(n); 
-                                } else {
-                                    return ; 
-                                }
-                            }
-                            ImageMode::BiImage => {
-                                if let Some((_, ref n)) = self.input_image_bi.0 {
-                                    cmd.arg(n); 
-                                } else {
-                                    return ; 
-                                }
-                                if let Some((_, ref n)) = self.input_image_bi.1 {
-                                    cmd.arg(n); 
-                                } else {
-                                    return ; 
-                                } 
-                            }
-                        }
-                        if !self.extra_arguments.is_empty() {
-                            cmd.arg(self.extra_arguments.as_str()); 
+                self.cancel(); 
+            }
+            ui.separator(); 
+            ui.add_space(20.); 
+            ui.label("Extra Arguments: "); 
+            ui.separator(); 
+            ui.text_edit_singleline(&mut self.extra_arguments); 
+            ui.separator(); 
+            ui.add_space(20.); 
+            ui.label("Output Format: "); 
+            ui.separator(); 
+            egui::ComboBox::from_id_source("output_format")
+                .selected_text(self.output_settings.name())
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(self.output_settings.follow_input, "Same as Input").clicked() {
+                        self.output_settings.follow_input = true; 
+                    }
+                    for f in OutputFormat::ENCODABLE {
+                        let selected = !self.output_settings.follow_input && self.output_settings.format == f; 
+                        if ui.selectable_label(selected, f.name()).clicked() {
+                            self.output_settings.format = f; 
+                            self.output_settings.follow_input = false; 
                         }
-                        let (tx, rx) = oneshot::channel(); 
-                        match self.image_mode {
-                            ImageMode::None => {
-                                self.output_image_none_rx = Some(rx);  
-                            }
-                            ImageMode::SingleImage => {
-                                self.output_image_singal_rx = Some(rx);  
-                            }
-                            ImageMode::BiImage => {
-                                self.output_image_bi_rx = Some(rx);   
-                            }
+                    }
+                }); 
+            if self.output_settings.follow_input || self.output_settings.format.uses_quality() {
+                ui.add(egui::Slider::new(&mut self.output_settings.quality, 1..=100).text("Quality")); 
+            }
+            ui.separator(); 
+            ui.add_space(20.); 
+            ui.label("Presets: "); 
+            ui.separator(); 
+            if let Some(ref e) = self.presets.error {
+                ui.colored_label(egui::Color32::RED, "Presets file is invalid, saving is disabled").on_hover_text(e.as_str()); 
+            }
+            let mut selected_preset = None; 
+            egui::ComboBox::from_id_source("preset_select")
+                .selected_text("Load Preset")
+                .show_ui(ui, |ui| {
+                    for (name, preset) in self.presets.presets.iter() {
+                        if ui.selectable_label(self.preset_name == *name, name).clicked() {
+                            selected_preset = Some((name.clone(), preset.clone())); 
                         }
-                        thread::spawn(move || {
-                            let mut cmd = cmd; 
-                            let tx = tx; 
-                            let cmd = cmd.status();  
-                            match cmd {
-                                Ok(e) => {
-                                    if e.success() {
-                                        let image = image::open("./outcome/result.jpg"); 
-                                        if let Ok(image) = image {
-                                            let _ = tx.send((image.to_rgba8(), "./outcome/result.jpg".to_string())); 
-                                        } else {
-                                            eprintln!("Error: {:?}", image.err()); 
-                                        } 
-                                    } else {
-                                        eprintln!("Error: {:?}", e);  
-                                    }
-                                },
-                                Err(e) => {
-                                    eprintln!("Error: {:?}", e);  
-                                },
-                            } 
-                        });
                     }
-                }(); 
+                }); 
+            if let Some((name, preset)) = selected_preset {
+                self.apply_preset(&preset); 
+                self.preset_name = name; 
             }
+            ui.text_edit_singleline(&mut self.preset_name); 
+            ui.horizontal(|ui| {
+                let step = self.pipeline_step(); 
+                let can_save = step.is_some() && !self.preset_name.trim().is_empty() && self.presets.error.is_none(); 
+                if ui.add_enabled(can_save, Button::new("Save")).clicked() {
+                    if let Some(step) = step {
+                        self.presets.presets.insert(self.preset_name.trim().to_string(), Preset::from_step(&step)); 
+                        if let Err(e) = self.presets.save(PRESET_FILE) {
+                            self.toasts.sender(ui.ctx()).error(format!("Cannot save presets: {}", e)); 
+                        }
+                    }
+                }
+                let can_delete = self.presets.presets.contains_key(self.preset_name.trim()) && self.presets.error.is_none(); 
+                if ui.add_enabled(can_delete, Button::new("Delete")).clicked() {
+                    self.presets.presets.remove(self.preset_name.trim()); 
+                    if let Err(e) = self.presets.save(PRESET_FILE) {
+                        self.toasts.sender(ui.ctx()).error(format!("Cannot save presets: {}", e)); 
+                    }
+                }
+            }); 
             ui.separator(); 
             ui.add_space(20.); 
-            ui.label("Extra Arguments: "); 
+            ui.label("Pipeline: "); 
             ui.separator(); 
-            ui.text_edit_singleline(&mut self.extra_arguments); 
+            let step = self.pipeline_step(); 
+            let pipeline_running = self.pipeline_job_rx.is_some(); 
+            if ui.add_enabled(step.is_some() && !pipeline_running, Button::new("Add to Pipeline")).clicked() {
+                if let Some(step) = step {
+                    self.pipeline.steps.push(step); 
+                    self.pipeline_results.clear(); 
+                    self.pipeline_window = true; 
+                }
+            }
+            if ui.selectable_label(self.pipeline_window, format!("Pipeline ({})", self.pipeline.steps.len())).clicked() {
+                self.pipeline_window = !self.pipeline_window; 
+            }
+            if ui.selectable_label(self.sweep_window, "Parameter Sweep").clicked() {
+                self.sweep_window = !self.sweep_window; 
+            }
+            if ui.add_enabled(self.compare_paths().is_some(), Button::new("Compare Before / After")).clicked() {
+                self.open_compare(ctx); 
+            }
+            if ui.selectable_label(self.diff_view.is_some(), "Difference / Metrics").clicked() {
+                self.diff_view = match self.diff_view {
+                    Some(_) => None, 
+                    None => {
+                        let (a, b) = self.compare_paths().unzip(); 
+                        Some(DiffView::new(a, b))
+                    }
+                }; 
+            }
+            if ui.selectable_label(self.stats_view.is_some(), "Color Statistics").clicked() {
+                self.stats_view = match self.stats_view {
+                    Some(_) => None, 
+                    None => Some(StatsView::new(Some(Slot::output(&self.image_mode).name().to_string()))), 
+                }; 
+            }
+            ui.separator(); 
+            ui.add_space(20.); 
+            if ui.selectable_label(self.shortcut_help, "Keyboard Shortcuts").on_hover_text(self.shortcuts.text(ctx, Action::ShowHelp)).clicked() {
+                self.shortcut_help = !self.shortcut_help; 
+            }
+            if ui.button("Reset to Defaults").on_hover_text("Forget the saved scripts, mode, arguments, images and window layout").clicked() {
+                self.restore(PersistedState::default()); 
+                reset_layout = true; 
+            }
         });
+        self.input_slot_rects.clear(); 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("Hello World!"); 
+            let side = self.slot_side(ui); 
             ui.with_layout(Layout::top_down_justified(eframe::emath::Align::Center), |ui| {
                 match self.image_mode {
                     ImageMode::None => {
                         ui.label("[Mode] No Image Selected. ");
                     }
                     ImageMode::SingleImage => {
-                        let click; 
-                        match self.input_image_single {
-                            Some((ref t, _)) => {
-                                // ui.image(t, [300., 300.]);
-                                let k = ui.add_sized([300., 300.], widgets::ImageButton::new(t, [300., 300.])); 
-                                click = k.clicked(); 
-                            },
-                            None => {
-                                let u = ui.allocate_response([300., 300.].into(), Sense::click()); 
-                                ui.put(u.rect, Spinner::new()); 
-                                click = u.clicked(); 
-                            },
-                        }
-                        if click {
-                            let (tx, rx) = oneshot::channel(); 
-                            self.input_image_singal_rx = Some(rx); 
-                            std::thread::spawn(move || {
-                                let task = rfd::AsyncFileDialog::new()
-                                    .set_directory(current_dir().unwrap_or("~".into()))
-                                    .add_filter("Images", &["jpg", "jpeg", "png"])
-                                    .pick_files(); 
-                                let task = futures::executor::block_on(task); 
-                                // dbg!(&task);
-                                if let Some(path) = task {
-                                    if path.len() != 1 {
-                                        return ; 
-                                    }
-                                    if let Some(path) = path.into_iter().nth(0) {
-                                        let path_str = path.path().to_string_lossy().into_owned(); 
-                                        let image = image::open(path.path()); 
-                                        if let Ok(image) = image {
-                                            let _ = tx.send((image.to_rgba8(), path_str)); 
-                                        } else {
-                                            eprintln!("Error: {:?}", image.err()); 
-                                        } 
-                                        return ; 
-                                    }
-                                } 
-                            }); 
-                        }
+                        self.input_slot_ui(ui, Slot::InputSingle, side); 
                     }
                     ImageMode::BiImage => {
-                        ui.allocate_ui_with_layout([700., 350.].into(), Layout::left_to_right(eframe::emath::Align::Center), |ui| {
-                        // ui.with_layout(Layout::left_to_right(eframe::emath::Align::Center), |ui| {
+                        // 槽位上方的标签与下方的尺寸信息也在这一行内
+                        let spacing = ui.spacing().item_spacing.y; 
+                        let height = ui.text_style_height(&egui::TextStyle::Body) + side + ui.text_style_height(&egui::TextStyle::Small) + 2. * spacing; 
+                        ui.allocate_ui_with_layout([ui.available_width(), height].into(), Layout::left_to_right(eframe::emath::Align::Center), |ui| {
                             // add two spinners, and handle the click event for select images 
-                            let click1; 
-                            match self.input_image_bi.0 {
-                                Some((ref t, _)) => {
-                                    let k = ui.add_sized([300., 300.], widgets::ImageButton::new(t, [300., 300.])); 
-                                    click1 = k.clicked(); 
-                                },
-                                None => {
-                                    let u = ui.allocate_response([300., 300.].into(), Sense::click()); 
-                                    ui.put(u.rect, Spinner::new()); 
-                                    click1 = u.clicked(); 
-                                },
-                            } 
-                            let click2; 
-                            match self.input_image_bi.1 {
-                                Some((ref t, _)) => {
-                                    let k = ui.add_sized([300., 300.], widgets::ImageButton::new(t, [300., 300.])); 
-                                    click2 = k.clicked(); 
-                                },
-                                None => {
-                                    let u = ui.allocate_response([300., 300.].into(), Sense::click()); 
-                                    ui.put(u.rect, Spinner::new()); 
-                                    click2 = u.clicked(); 
-                                },
-                            } 
-                            if click1 {
-                                let (tx, rx) = oneshot::channel(); 
-                                self.input_image_bi1_rx = Some(rx); 
-                                std::thread::spawn(move || {
-                                    let task = rfd::AsyncFileDialog::new()
-                                        .set_directory(current_dir().unwrap_or("~".into()))
-                                        .add_filter("Images", &["jpg", "jpeg", "png"])
-                                        .pick_files(); 
-                                    let task = futures::executor::block_on(task); 
-                                    if let Some(path) = task {
-                                        if path.len() != 1 {
-                                            return ; 
-                                        }
-                                        if let Some(path) = path.into_iter().nth(0) {
-                                            let path_str = path.path().to_string_lossy().into_owned(); 
-                                            let image = image::open(path.path()); 
-                                            if let Ok(image) = image {
-                                                let _ = tx.send((image.to_rgba8(), path_str)); 
-                                            } else {
-                                                eprintln!("Error: {:?}", image.err()); 
-                                            } 
-                                            return ; 
-                                        }
-                                    } 
-                                });  
-                            } 
-                            if click2 {
-                                let (tx, rx) = oneshot::channel(); 
-                                self.input_image_bi2_rx = Some(rx); 
-                                std::thread::spawn(move || {
-                                    let task = rfd::AsyncFileDialog::new()
-                                        .set_directory(current_dir().unwrap_or("~".into()))
-                                        .add_filter("Images", &["jpg", "jpeg", "png"])
-                                        .pick_files(); 
-                                    let task = futures::executor::block_on(task); 
-                                    if let Some(path) = task {
-                                        if path.len() != 1 {
-                                            return ; 
-                                        }
-                                        if let Some(path) = path.into_iter().nth(0) {
-                                            let path_str = path.path().to_string_lossy().into_owned(); 
-                                            let image = image::open(path.path()); 
-                                            if let Ok(image) = image {
-                                                let _ = tx.send((image.to_rgba8(), path_str)); 
-                                            } else {
-                                                eprintln!("Error: {:?}", image.err()); 
-                                            } 
-                                            return ; 
-                                        }
-                                    } 
-                                }); 
+                            self.input_slot_ui(ui, Slot::InputBi1, side); 
+                            let swap = ui.add_sized([SWAP_BUTTON_WIDTH, SWAP_BUTTON_WIDTH], Button::new("⇄"))
+                                .on_hover_text(format!("Swap {} and {}", self.slot_label(Slot::InputBi1), self.slot_label(Slot::InputBi2))); 
+                            if swap.clicked() {
+                                self.swap_inputs(); 
                             }
+                            self.input_slot_ui(ui, Slot::InputBi2, side); 
                         });
                     }
                 }
                 ui.separator(); 
                 ui.with_layout(Layout::top_down(eframe::emath::Align::Center), |ui| {
-                    let mut exist = false; 
-                    let click; 
                     // display the result 
-                    match self.image_mode {
-                        ImageMode::None => {
-                            match self.output_image_none {
-                                Some((ref t, _)) => {
-                                    let c = ui.add_sized([300., 300.], widgets::ImageButton::new(t, [300., 300.])); 
-                                    exist = true; 
-                                    click = c.clicked(); 
-                                },
-                                None => {
-                                    let u = ui.allocate_response([300., 300.].into(), Sense::click()); 
-                                    ui.put(u.rect, Spinner::new()); 
-                                    click = u.clicked(); 
-                                }, 
-                            }
-                        }
-                        ImageMode::SingleImage => {
-                            match self.output_image_single {
-                                Some((ref t, _)) => {
-                                    let c = ui.add_sized([300., 300.], widgets::ImageButton::new(t, [300., 300.])); 
-                                    exist = true; 
-                                    click = c.clicked(); 
-                                },
-                                None => {
-                                    let u = ui.allocate_response([300., 300.].into(), Sense::click()); 
-                                    ui.put(u.rect, Spinner::new()); 
-                                    click = u.clicked(); 
-                                }, 
-                            } 
-                        }
-                        ImageMode::BiImage => {
-                            match self.output_image_bi {
-                                Some((ref t, _)) => {
-                                    let c = ui.add_sized([300., 300.], widgets::ImageButton::new(t, [300., 300.])); 
-                                    exist = true; 
-                                    click = c.clicked(); 
-                                },
-                                None => {
-                                    let u = ui.allocate_response([300., 300.].into(), Sense::click()); 
-                                    ui.put(u.rect, Spinner::new()); 
-                                    click = u.clicked(); 
-                                }, 
-                            }  
-                        }
-                    }
-                    if exist && click {
-                        // copy the image to clipboard 
-                        thread::spawn(|| {
-                            let clipboard = Clipboard::new(); 
-                            let mut clip; 
-                            match clipboard {
-                                Ok(c) => clip = c, 
-                                Err(_) => return ,
-                            }
-                            // open image from './outcome/result.jpg' 
-                            let image = image::open("./outcome/result.jpg"); 
-                            if let Ok(image) = image {
-                                let image = image.to_rgba8(); 
-                                let _ = clip.set_image(arboard::ImageData { width: image.width() as usize, height: image.height() as usize, bytes: {
-                                    let c = image.into_raw().into_iter().map(|a| a as u8).collect();
-                                    Cow::Owned(c)
-                                } }); 
-                            } else {
-                                eprintln!("Error: {:?}", image.err()); 
-                            }  
-                        });
+                    let output = Slot::output(&self.image_mode); 
+                    self.output_slot_ui(ui, output, side); 
+                    if self.slot_image(output).is_some() {
+                        ui.horizontal(|ui| self.use_as_input_buttons(ui, output)); 
                     }
                 });
             }); 
         }); 
+        self.handle_dropped_files(ctx); 
+        self.handle_paste(ctx); 
+        self.handle_shortcuts(ctx); 
+        self.keyboard_captured = self.keyboard_busy(ctx); 
+        if self.shortcut_help {
+            let mut open = true; 
+            self.shortcuts.help_window(ctx, &mut open); 
+            self.shortcut_help &= open; 
+        }
+        self.toasts.show(ctx); 
+        if reset_layout {
+            ctx.memory_mut(|m| *m = Default::default()); 
+            frame.set_window_size(self.config.window_size.into()); 
+        }
     }
 }
\ No newline at end of file
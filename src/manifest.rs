@@ -0,0 +1,121 @@
+//! 脚本清单：脚本旁的同名 `.toml` 文件，描述脚本与它的输入
+//!
+//! ```toml
+//! description = "Neural style transfer"
+//! tags = ["style", "gan"]
+//! inputs = ["Content", "Style"]
+//! ```
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Deserialize;
+
+/// 脚本路径到清单的映射
+pub type Manifests = BTreeMap<String, Manifest>; 
+
+/// 脚本清单；没有清单文件时为空
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Manifest {
+    /// 脚本说明
+    pub description: String, 
+    /// 搜索用的标签
+    pub tags: Vec<String>, 
+    /// 各输入图像的名称，按传给脚本的顺序
+    pub inputs: Vec<String>, 
+}
+
+impl Manifest {
+    /// 脚本的清单文件：`a.py` 对应 `a.toml`
+    pub fn path_for(script: impl AsRef<Path>) -> PathBuf {
+        script.as_ref().with_extension("toml")
+    }
+
+    /// 读取脚本的清单；不存在或无法解析时返回空清单
+    pub fn load_for(script: impl AsRef<Path>) -> Manifest {
+        let path = Self::path_for(script); 
+        match std::fs::read_to_string(&path) {
+            Ok(s) => match toml::from_str(&s) {
+                Ok(m) => m, 
+                Err(e) => {
+                    eprintln!("Error: {}: {}", path.display(), e); 
+                    Manifest::default()
+                }
+            }, 
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Manifest::default(), 
+            Err(e) => {
+                eprintln!("Error: {}: {}", path.display(), e); 
+                Manifest::default()
+            }
+        }
+    }
+
+    /// 第 n 个输入的名称
+    pub fn input_label(&self, n: usize) -> Option<&str> {
+        self.inputs.get(n).map(String::as_str).filter(|s| !s.is_empty())
+    }
+}
+
+/// 扫描线程中的清单缓存：清单文件的修改时间不变时不再读取，解析错误也只报告一次
+#[derive(Debug, Default)]
+pub struct ManifestCache {
+    entries: BTreeMap<String, (Option<SystemTime>, Manifest)>, 
+}
+
+impl ManifestCache {
+    /// 一次扫描结果中所有脚本的清单；只重新读取新增或修改过的清单文件
+    pub fn load_all(&mut self, scripts: &[String]) -> Manifests {
+        let mut entries = BTreeMap::new(); 
+        for script in scripts {
+            let modified = std::fs::metadata(Manifest::path_for(script)).and_then(|m| m.modified()).ok(); 
+            let entry = match self.entries.remove(script) {
+                Some(cached) if cached.0 == modified => cached, 
+                // 没有清单文件时不必读取
+                _ if modified.is_none() => (None, Manifest::default()), 
+                _ => (modified, Manifest::load_for(script)), 
+            }; 
+            entries.insert(script.clone(), entry); 
+        }
+        self.entries = entries; 
+        self.entries.iter().map(|(s, (_, m))| (s.clone(), m.clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    /// 在临时目录中写入脚本的清单，并设置清单的修改时间
+    fn write_manifest(dir: &Path, text: &str, modified: SystemTime) -> String {
+        let script = dir.join("style.py"); 
+        let path = Manifest::path_for(&script); 
+        std::fs::write(&path, text).unwrap(); 
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap(); 
+        script.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn reloads_only_changed_manifests() {
+        let dir = std::env::temp_dir().join(format!("image-transfer-manifest-test-{}", std::process::id())); 
+        std::fs::create_dir_all(&dir).unwrap(); 
+        let t = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000); 
+        let script = write_manifest(&dir, "tags = [\"a\"]", t); 
+        let scripts = [script.clone()]; 
+        let mut cache = ManifestCache::default(); 
+        assert_eq!(cache.load_all(&scripts)[&script].tags, ["a"]); 
+        // 修改时间不变时使用缓存
+        write_manifest(&dir, "tags = [\"b\"]", t); 
+        assert_eq!(cache.load_all(&scripts)[&script].tags, ["a"]); 
+        write_manifest(&dir, "tags = [\"c\"]", t + Duration::from_secs(1)); 
+        assert_eq!(cache.load_all(&scripts)[&script].tags, ["c"]); 
+        std::fs::remove_file(Manifest::path_for(&script)).unwrap(); 
+        assert!(cache.load_all(&scripts)[&script].tags.is_empty()); 
+        assert!(cache.load_all(&[]).is_empty()); 
+        std::fs::remove_dir_all(dir).unwrap(); 
+    }
+}
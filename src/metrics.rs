@@ -0,0 +1,150 @@
+//! 两幅图像的差异：逐像素差值热图、PSNR、SSIM、平均颜色偏移
+
+use image::{ImageBuffer, Rgba};
+
+/// SSIM 的窗口边长
+pub const SSIM_WINDOW : u32 = 8; 
+
+/// 差异指标
+#[derive(Clone, Debug)]
+pub struct Metrics {
+    /// 峰值信噪比（dB）；两幅图像完全相同时为无穷大
+    pub psnr: f64, 
+    /// 亮度通道的结构相似度（-1 - 1）
+    pub ssim: f64, 
+    /// 每个通道 b - a 的平均值
+    pub mean_shift: [f64; 3], 
+    /// 最大的通道差值
+    pub max_difference: u8, 
+}
+
+/// 计算结果：指标与差值热图
+pub struct Difference {
+    pub metrics: Metrics, 
+    pub heatmap: ImageBuffer<Rgba<u8>, Vec<u8>>, 
+}
+
+/// 比较两幅尺寸相同的图像；尺寸不同时返回 None。
+/// 热图按 gain 放大差值：黑 → 红 → 黄 → 白
+pub fn compare(a: &ImageBuffer<Rgba<u8>, Vec<u8>>, b: &ImageBuffer<Rgba<u8>, Vec<u8>>, gain: f32) -> Option<Difference> {
+    if a.dimensions() != b.dimensions() || a.width() == 0 || a.height() == 0 {
+        return None; 
+    }
+    let mut squared = 0f64; 
+    let mut shift = [0f64; 3]; 
+    let mut max_difference = 0u8; 
+    let heatmap = ImageBuffer::from_fn(a.width(), a.height(), |x, y| {
+        let (pa, pb) = (a.get_pixel(x, y), b.get_pixel(x, y)); 
+        let mut d = 0u8; 
+        for c in 0..3 {
+            let diff = pb[c] as f64 - pa[c] as f64; 
+            squared += diff * diff; 
+            shift[c] += diff; 
+            d = d.max(pa[c].abs_diff(pb[c])); 
+        }
+        max_difference = max_difference.max(d); 
+        heat(d as f32 / 255. * gain)
+    }); 
+    let pixels = a.width() as f64 * a.height() as f64; 
+    let mse = squared / (pixels * 3.); 
+    let psnr = if mse == 0. { f64::INFINITY } else { 10. * (255. * 255. / mse).log10() }; 
+    let metrics = Metrics {
+        psnr, 
+        ssim: ssim(a, b), 
+        mean_shift: shift.map(|s| s / pixels), 
+        max_difference, 
+    }; 
+    Some(Difference { metrics, heatmap })
+}
+
+/// 热图颜色，t 为 0 - 1
+fn heat(t: f32) -> Rgba<u8> {
+    let t = t.clamp(0., 1.) * 3.; 
+    let channel = |v: f32| (v.clamp(0., 1.) * 255.).round() as u8; 
+    Rgba([channel(t), channel(t - 1.), channel(t - 2.), 255])
+}
+
+/// 像素亮度（BT.601）
+pub fn luma(p: &Rgba<u8>) -> f64 {
+    0.299 * p[0] as f64 + 0.587 * p[1] as f64 + 0.114 * p[2] as f64
+}
+
+/// 亮度通道上不重叠窗口的平均 SSIM
+pub fn ssim(a: &ImageBuffer<Rgba<u8>, Vec<u8>>, b: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> f64 {
+    const C1 : f64 = (0.01 * 255.) * (0.01 * 255.); 
+    const C2 : f64 = (0.03 * 255.) * (0.03 * 255.); 
+    let (w, h) = a.dimensions(); 
+    let mut total = 0.; 
+    let mut windows = 0; 
+    for wy in (0..h).step_by(SSIM_WINDOW as usize) {
+        for wx in (0..w).step_by(SSIM_WINDOW as usize) {
+            let (x1, y1) = ((wx + SSIM_WINDOW).min(w), (wy + SSIM_WINDOW).min(h)); 
+            let n = ((x1 - wx) * (y1 - wy)) as f64; 
+            let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0., 0., 0., 0., 0.); 
+            for y in wy..y1 {
+                for x in wx..x1 {
+                    let (la, lb) = (luma(a.get_pixel(x, y)), luma(b.get_pixel(x, y))); 
+                    sa += la; 
+                    sb += lb; 
+                    saa += la * la; 
+                    sbb += lb * lb; 
+                    sab += la * lb; 
+                }
+            }
+            let (ma, mb) = (sa / n, sb / n); 
+            let va = saa / n - ma * ma; 
+            let vb = sbb / n - mb * mb; 
+            let cov = sab / n - ma * mb; 
+            total += ((2. * ma * mb + C1) * (2. * cov + C2)) / ((ma * ma + mb * mb + C1) * (va + vb + C2)); 
+            windows += 1; 
+        }
+    }
+    total / windows as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(offset: u8) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(20, 12, |x, y| Rgba([(x * 10) as u8 + offset, (y * 20) as u8 + offset, 100 + offset, 255]))
+    }
+
+    #[test]
+    fn identical_images() {
+        let a = gradient(0); 
+        let d = compare(&a, &a, 1.).unwrap(); 
+        assert_eq!(d.metrics.psnr, f64::INFINITY); 
+        assert!((d.metrics.ssim - 1.).abs() < 1e-9, "{}", d.metrics.ssim); 
+        assert_eq!(d.metrics.mean_shift, [0.; 3]); 
+        assert_eq!(d.metrics.max_difference, 0); 
+        assert!(d.heatmap.pixels().all(|p| *p == Rgba([0, 0, 0, 255]))); 
+    }
+
+    #[test]
+    fn constant_offset() {
+        let (a, b) = (gradient(0), gradient(10)); 
+        let d = compare(&a, &b, 1.).unwrap(); 
+        // MSE = 100
+        let expected = 10. * (255f64 * 255. / 100.).log10(); 
+        assert!((d.metrics.psnr - expected).abs() < 1e-9, "{}", d.metrics.psnr); 
+        assert!((d.metrics.psnr - 28.13).abs() < 0.01); 
+        assert_eq!(d.metrics.mean_shift, [10.; 3]); 
+        assert_eq!(d.metrics.max_difference, 10); 
+        // 亮度整体平移只影响均值项，SSIM 略小于 1
+        assert!(d.metrics.ssim < 1. && d.metrics.ssim > 0.9, "{}", d.metrics.ssim); 
+    }
+
+    #[test]
+    fn rejects_different_sizes() {
+        let b = ImageBuffer::from_pixel(4, 4, Rgba([0, 0, 0, 255])); 
+        assert!(compare(&gradient(0), &b, 1.).is_none()); 
+    }
+
+    #[test]
+    fn heat_scale() {
+        assert_eq!(heat(0.), Rgba([0, 0, 0, 255])); 
+        assert_eq!(heat(1. / 3.), Rgba([255, 0, 0, 255])); 
+        assert_eq!(heat(2.), Rgba([255, 255, 255, 255])); 
+    }
+}
@@ -0,0 +1,114 @@
+use std::ffi::OsString;
+use std::path::Path;
+
+use crate::image_format::OutputSettings;
+use crate::image_mode::ImageMode;
+use crate::script_execution::Executor;
+use crate::script_option::ScriptOption;
+
+/// 流水线默认输出目录
+pub const PIPELINE_OUTPUT_DIR : &str = "./outcome/pipeline"; 
+
+/// 流水线中的一步
+#[derive(Clone, Debug)]
+pub struct PipelineStep {
+    pub script_option: ScriptOption, 
+    pub script: String, 
+    pub image_mode: ImageMode, 
+    pub other_args: String, 
+}
+
+/// 脚本流水线：每一步的输出替换下一步的第一个输入
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline {
+    pub steps: Vec<PipelineStep>, 
+}
+
+impl Pipeline {
+    /// 第 n 步（从 0 开始）的输出文件
+    pub fn step_output(output_dir: &str, n: usize, settings: &OutputSettings) -> OsString {
+        settings.apply(Path::new(output_dir).join(format!("step-{}", n + 1)))
+    }
+
+    /// 展开为依次执行的执行器；inputs 为第一步之前已载入的图像
+    pub fn executors(&self, inputs: &[OsString], output_dir: &str, settings: &OutputSettings) -> Result<Vec<Executor>, String> {
+        let mut current = inputs.to_vec(); 
+        let mut runs = Vec::with_capacity(self.steps.len()); 
+        for (n, step) in self.steps.iter().enumerate() {
+            let count = step.image_mode.image_count(); 
+            if current.len() < count {
+                return Err(format!("step {} needs {} input(s), only {} available", n + 1, count, current.len())); 
+            }
+            let images = current[..count].to_vec(); 
+            let settings = settings.resolve(&images); 
+            let output = Self::step_output(output_dir, n, &settings); 
+            runs.push(Executor {
+                script_option: step.script_option.clone(), 
+                script: step.script.clone().into(), 
+                output: output.clone(), 
+                output_settings: settings, 
+                images, 
+                other_args: step.other_args.clone(), 
+            }); 
+            match current.first_mut() {
+                Some(first) => *first = output, 
+                None => current.push(output), 
+            }
+        }
+        Ok(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::image_format::OutputFormat;
+
+    fn step(script: &str, image_mode: ImageMode) -> PipelineStep {
+        PipelineStep { script_option: ScriptOption::DirectExecute, script: script.to_string(), image_mode, other_args: String::new() }
+    }
+
+    fn settings() -> OutputSettings {
+        OutputSettings::from_path("out.png")
+    }
+
+    #[test]
+    fn chains_outputs_into_the_first_input() {
+        let pipeline = Pipeline { steps: vec![step("a", ImageMode::BiImage), step("b", ImageMode::SingleImage), step("c", ImageMode::BiImage)] }; 
+        let runs = pipeline.executors(&["x.png".into(), "y.png".into()], "out", &settings()).unwrap(); 
+        let step_1 = Pipeline::step_output("out", 0, &settings()); 
+        let step_2 = Pipeline::step_output("out", 1, &settings()); 
+        assert_eq!(runs[0].images, ["x.png", "y.png"]); 
+        assert_eq!(runs[1].images, std::slice::from_ref(&step_1)); 
+        assert_eq!(runs[2].images, [step_2, "y.png".into()]); 
+        assert_eq!(runs[0].output, step_1); 
+        assert_eq!(runs[2].output, Pipeline::step_output("out", 2, &settings())); 
+    }
+
+    #[test]
+    fn generator_step_provides_the_next_input() {
+        let pipeline = Pipeline { steps: vec![step("gen", ImageMode::None), step("b", ImageMode::SingleImage)] }; 
+        let runs = pipeline.executors(&[], "out", &settings()).unwrap(); 
+        assert!(runs[0].images.is_empty()); 
+        assert_eq!(runs[1].images, [Pipeline::step_output("out", 0, &settings())]); 
+    }
+
+    #[test]
+    fn reports_missing_inputs() {
+        let pipeline = Pipeline { steps: vec![step("a", ImageMode::SingleImage), step("b", ImageMode::BiImage)] }; 
+        let e = pipeline.executors(&["x.png".into()], "out", &settings()).unwrap_err(); 
+        assert!(e.starts_with("step 2 needs 2 input(s)"), "{}", e); 
+        assert!(Pipeline { steps: vec![step("a", ImageMode::SingleImage)] }.executors(&[], "out", &settings()).is_err()); 
+    }
+
+    #[test]
+    fn follows_the_format_of_each_step_input() {
+        let follow = OutputSettings { follow_input: true, ..settings() }; 
+        let pipeline = Pipeline { steps: vec![step("a", ImageMode::SingleImage), step("b", ImageMode::SingleImage)] }; 
+        let runs = pipeline.executors(&["x.jpg".into()], "out", &follow).unwrap(); 
+        assert_eq!(runs[0].output_settings.format, OutputFormat::Jpeg); 
+        assert_eq!(runs[1].output_settings.format, OutputFormat::Jpeg); 
+        assert_eq!(runs[1].images, [Pipeline::step_output("out", 0, &runs[0].output_settings)]); 
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::image_mode::ImageMode;
+use crate::pipeline::PipelineStep;
+use crate::script_option::ScriptOption;
+
+/// 预设文件
+pub const PRESET_FILE : &str = "./presets.toml"; 
+
+/// 一组完整的执行配置：脚本、解释器、图像模式与额外参数
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Preset {
+    /// 是否为 Native 脚本
+    #[serde(default)]
+    pub native: bool, 
+    /// Python 解释器；None 使用默认解释器
+    pub interpreter: Option<String>, 
+    /// 脚本路径
+    pub script: String, 
+    pub image_mode: ImageMode, 
+    #[serde(default)]
+    pub extra_arguments: String, 
+}
+
+impl Preset {
+    pub fn from_step(step: &PipelineStep) -> Preset {
+        let (native, interpreter) = match step.script_option {
+            ScriptOption::DirectExecute => (true, None), 
+            ScriptOption::PyExecute(ref i) => (false, i.as_ref().map(|i| i.to_string_lossy().into_owned())), 
+        }; 
+        Preset {
+            native, 
+            interpreter, 
+            script: step.script.clone(), 
+            image_mode: step.image_mode.clone(), 
+            extra_arguments: step.other_args.clone(), 
+        }
+    }
+}
+
+/// 按名称保存的预设
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Presets {
+    #[serde(default)]
+    pub presets: BTreeMap<String, Preset>, 
+    /// 读取失败的原因；此时拒绝保存，以免覆盖用户的文件
+    #[serde(skip)]
+    pub error: Option<String>, 
+}
+
+impl Presets {
+    /// 读取预设文件；文件不存在时返回空集合，读取或解析失败时返回带有 error 的空集合
+    pub fn load(path: impl AsRef<Path>) -> Presets {
+        let path = path.as_ref(); 
+        let error = match std::fs::read_to_string(path) {
+            Ok(s) => match toml::from_str(&s) {
+                Ok(p) => return p, 
+                Err(e) => format!("{}: {}", path.display(), e), 
+            }, 
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Presets::default(), 
+            Err(e) => format!("{}: {}", path.display(), e), 
+        }; 
+        eprintln!("Error: {}", error); 
+        Presets { error: Some(error), ..Presets::default() }
+    }
+
+    /// 写入预设文件；读取时出错则拒绝写入
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(ref e) = self.error {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("not saving presets, the file failed to load: {}", e))); 
+        }
+        let s = toml::to_string(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?; 
+        std::fs::write(path, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每个测试使用各自的临时文件
+    fn temp_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("image-transfer-test-{}-{}.toml", name, std::process::id()))
+    }
+
+    fn preset() -> Preset {
+        Preset {
+            native: false, 
+            interpreter: None, 
+            script: "style.py".to_string(), 
+            image_mode: ImageMode::SingleImage, 
+            extra_arguments: "-w=2".to_string(), 
+        }
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let presets = Presets::load(temp_file("missing")); 
+        assert!(presets.presets.is_empty()); 
+        assert!(presets.error.is_none()); 
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_file("round-trip"); 
+        let mut presets = Presets::default(); 
+        presets.presets.insert("a".to_string(), preset()); 
+        presets.save(&path).unwrap(); 
+        let loaded = Presets::load(&path); 
+        assert!(loaded.error.is_none()); 
+        assert_eq!(loaded.presets["a"].script, "style.py"); 
+        assert_eq!(loaded.presets["a"].extra_arguments, "-w=2"); 
+        std::fs::remove_file(path).unwrap(); 
+    }
+
+    #[test]
+    fn invalid_file_is_not_overwritten() {
+        let path = temp_file("invalid"); 
+        std::fs::write(&path, "[presets.a\nscript = 1").unwrap(); 
+        let mut presets = Presets::load(&path); 
+        assert!(presets.presets.is_empty()); 
+        assert!(presets.error.as_deref().unwrap().starts_with(&path.display().to_string())); 
+        presets.presets.insert("a".to_string(), preset()); 
+        let e = presets.save(&path).unwrap_err(); 
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData); 
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[presets.a\nscript = 1"); 
+        std::fs::remove_file(path).unwrap(); 
+    }
+}
@@ -0,0 +1,117 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Serializer};
+
+/// 一次脚本执行的来源记录，写在输出文件旁边
+#[derive(Clone, Debug, Serialize)]
+pub struct Provenance {
+    /// 执行的脚本
+    pub script: String, 
+    /// Python 解释器；Native 模式下为 None
+    pub interpreter: Option<String>, 
+    /// 输入图像路径
+    pub inputs: Vec<String>, 
+    /// 额外参数
+    pub extra_arguments: String, 
+    /// 输出文件
+    pub output: String, 
+    /// 输出格式扩展名
+    pub output_format: String, 
+    /// 输出质量
+    pub quality: u8, 
+    /// 开始时间（UNIX 秒）
+    pub started_at: u64, 
+    /// 执行耗时，记录为毫秒
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration, 
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
+fn invalid_data(e: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+impl Provenance {
+    /// 记录文件路径：`<output>.provenance.toml`
+    pub fn sidecar_path(output: &Path) -> PathBuf {
+        let mut s = output.as_os_str().to_owned(); 
+        s.push(".provenance.toml"); 
+        PathBuf::from(s)
+    }
+
+    /// 当前 UNIX 时间（秒）
+    pub fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+    }
+
+    /// 以 TOML 文本输出
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    /// 把 source 旁的记录文件复制到 dest 旁，并记下另存的位置；返回新记录文件路径
+    pub fn copy_sidecar(source: &Path, dest: &Path) -> io::Result<PathBuf> {
+        let s = std::fs::read_to_string(Self::sidecar_path(source))?; 
+        let mut table : toml::Table = toml::from_str(&s).map_err(invalid_data)?; 
+        table.insert("saved_as".to_string(), dest.to_string_lossy().into_owned().into()); 
+        let path = Self::sidecar_path(dest); 
+        std::fs::write(&path, toml::to_string(&table).map_err(invalid_data)?)?; 
+        Ok(path)
+    }
+
+    /// 写入输出文件旁的记录文件，返回记录文件路径
+    pub fn write(&self) -> io::Result<PathBuf> {
+        let path = Self::sidecar_path(self.output.as_ref()); 
+        std::fs::write(&path, self.to_toml().map_err(invalid_data)?)?; 
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provenance(output: &Path) -> Provenance {
+        Provenance {
+            script: "./pyscripts/a \"b\"\u{200b}.py".to_string(), 
+            interpreter: None, 
+            inputs: vec!["C:\\in\\x.png".to_string(), "tab\there".to_string()], 
+            extra_arguments: "--w=1 '\u{7f}'".to_string(), 
+            output: output.to_string_lossy().into_owned(), 
+            output_format: "png".to_string(), 
+            quality: 90, 
+            started_at: 1_700_000_000, 
+            duration: Duration::from_millis(1234), 
+        }
+    }
+
+    #[test]
+    fn writes_valid_toml() {
+        let p = provenance(Path::new("out.png")); 
+        let table : toml::Table = toml::from_str(&p.to_toml().unwrap()).unwrap(); 
+        assert_eq!(table["script"].as_str(), Some(p.script.as_str())); 
+        assert_eq!(table["inputs"][1].as_str(), Some("tab\there")); 
+        assert_eq!(table["extra_arguments"].as_str(), Some(p.extra_arguments.as_str())); 
+        assert_eq!(table["duration_ms"].as_integer(), Some(1234)); 
+        assert!(!table.contains_key("interpreter")); 
+    }
+
+    #[test]
+    fn copies_sidecar_with_saved_as() {
+        let dir = std::env::temp_dir().join(format!("image-transfer-provenance-test-{}", std::process::id())); 
+        std::fs::create_dir_all(&dir).unwrap(); 
+        let source = dir.join("result.png"); 
+        let dest = dir.join("saved \u{200b}\"x\".png"); 
+        provenance(&source).write().unwrap(); 
+        let path = Provenance::copy_sidecar(&source, &dest).unwrap(); 
+        let table : toml::Table = toml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap(); 
+        assert_eq!(table["saved_as"].as_str(), Some(dest.to_string_lossy().as_ref())); 
+        assert_eq!(table["quality"].as_integer(), Some(90)); 
+        std::fs::remove_dir_all(dir).unwrap(); 
+    }
+}
@@ -1,14 +1,124 @@
 use std::ffi::OsString;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-use futures::channel::oneshot::Sender;
 use image::{ImageBuffer, Rgba};
 
-use crate::script_option::ScriptOption;
+use crate::image_format::OutputSettings;
+use crate::provenance::Provenance;
+use crate::script_option::{ScriptOption, DEFAULT_PYTHON_EXECUTOR};
 
+/// 默认输出文件
+pub const DEFAULT_OUTPUT : &str = "./outcome/result.png"; 
+
+/// 传给脚本的输出格式环境变量
+pub const FORMAT_ENV : &str = "IMAGE_TRANSFER_FORMAT"; 
+/// 传给脚本的输出质量环境变量
+pub const QUALITY_ENV : &str = "IMAGE_TRANSFER_QUALITY"; 
+
+/// 可取消的执行检查取消标记的间隔
+const CANCEL_POLL_INTERVAL : Duration = Duration::from_millis(50); 
+
+/// 一次脚本执行：`<script> <output> [inputs...] [other_args]`，
+/// 输出格式与质量通过环境变量 `IMAGE_TRANSFER_FORMAT` / `IMAGE_TRANSFER_QUALITY` 传递
+#[derive(Clone, Debug)]
 pub struct Executor {
     pub script_option: ScriptOption, 
-    pub image1: OsString, 
-    pub image2: Option<OsString>, 
+    pub script: OsString, 
+    pub output: OsString, 
+    pub output_settings: OutputSettings, 
+    pub images: Vec<OsString>, 
     pub other_args: String,
-    pub return_channel: Sender<ImageBuffer<Rgba<u8>, Vec<u8>>>, 
-}
\ No newline at end of file
+}
+
+/// 执行失败原因
+#[derive(Debug)]
+pub enum ExecuteError {
+    /// 无法启动进程
+    Spawn(std::io::Error), 
+    /// 脚本以非零状态退出
+    Status(ExitStatus), 
+    /// 无法读取输出图像
+    Output(image::ImageError), 
+    /// 执行被取消，脚本进程已结束
+    Cancelled, 
+}
+
+impl Executor {
+    /// 构造命令行
+    pub fn command(&self) -> Command {
+        let mut cmd; 
+        match self.script_option {
+            ScriptOption::DirectExecute => {
+                cmd = Command::new(&self.script); 
+            }
+            ScriptOption::PyExecute(ref interpreter) => {
+                cmd = Command::new(interpreter.as_deref().unwrap_or(DEFAULT_PYTHON_EXECUTOR.as_ref())); 
+                cmd.arg(&self.script); 
+            }
+        }
+        cmd.env(FORMAT_ENV, self.output_settings.format.extension()); 
+        cmd.env(QUALITY_ENV, self.output_settings.quality.to_string()); 
+        cmd.arg(&self.output); 
+        cmd.args(&self.images); 
+        if !self.other_args.is_empty() {
+            cmd.arg(self.other_args.as_str()); 
+        }
+        cmd
+    }
+
+    /// 阻塞执行脚本，成功后写入来源记录并读取输出图像
+    pub fn execute(&self) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, ExecuteError> {
+        self.execute_cancellable(&AtomicBool::new(false))
+    }
+
+    /// 同 execute；cancel 被置位时结束脚本进程并返回 Cancelled
+    pub fn execute_cancellable(&self, cancel: &AtomicBool) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, ExecuteError> {
+        if let Some(parent) = Path::new(&self.output).parent() {
+            let _ = std::fs::create_dir_all(parent); 
+        }
+        let started_at = Provenance::now(); 
+        let clock = Instant::now(); 
+        let mut child = self.command().spawn().map_err(ExecuteError::Spawn)?; 
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(ExecuteError::Spawn)? {
+                break status; 
+            }
+            if cancel.load(Ordering::Relaxed) {
+                let _ = child.kill(); 
+                let _ = child.wait(); 
+                return Err(ExecuteError::Cancelled); 
+            }
+            std::thread::sleep(CANCEL_POLL_INTERVAL); 
+        }; 
+        if !status.success() {
+            return Err(ExecuteError::Status(status)); 
+        }
+        let provenance = self.provenance(started_at, clock.elapsed()); 
+        if let Err(e) = provenance.write() {
+            eprintln!("Error: {:?}", e); 
+        }
+        let image = image::open(&self.output).map_err(ExecuteError::Output)?; 
+        Ok(image.to_rgba8())
+    }
+
+    /// 本次执行的来源记录
+    pub fn provenance(&self, started_at: u64, duration: std::time::Duration) -> Provenance {
+        Provenance {
+            script: self.script.to_string_lossy().into_owned(), 
+            interpreter: match self.script_option {
+                ScriptOption::DirectExecute => None, 
+                ScriptOption::PyExecute(ref i) => Some(i.as_deref().unwrap_or(DEFAULT_PYTHON_EXECUTOR.as_ref()).to_string_lossy().into_owned()), 
+            }, 
+            inputs: self.images.iter().map(|i| i.to_string_lossy().into_owned()).collect(), 
+            extra_arguments: self.other_args.clone(), 
+            output: self.output.to_string_lossy().into_owned(), 
+            output_format: self.output_settings.format.extension().to_string(), 
+            quality: self.output_settings.quality, 
+            started_at, 
+            duration, 
+        }
+    }
+}
@@ -6,11 +6,18 @@ pub enum ImageInput {
     TwoFiles, 
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 /// 脚本执行模式
 pub enum ScriptOption {
     /// 直接进程执行
     DirectExecute, 
     /// Py 脚本执行，选择 Python 解释器
     PyExecute(Option<OsString>),
-}
\ No newline at end of file
+}
+
+/// 未指定解释器时使用的 Python 程序
+#[cfg(target_os = "windows")]
+pub const DEFAULT_PYTHON_EXECUTOR : &str = "./python.exe"; 
+/// 未指定解释器时使用的 Python 程序
+#[cfg(not(target_os = "windows"))]
+pub const DEFAULT_PYTHON_EXECUTOR : &str = "./python"; 
@@ -0,0 +1,36 @@
+use std::ffi::OsString;
+use std::path::Path;
+
+/// Python 脚本目录
+pub const PY_SCRIPT_ROOT : &str = "./pyscripts"; 
+/// Native 脚本目录
+pub const NATIVE_SCRIPT_ROOT : &str = "./nativescripts"; 
+/// Python 脚本扩展名
+pub const PY_SCRIPT_EXTENSION : &str = "py"; 
+/// Native 脚本扩展名
+pub const NATIVE_SCRIPT_EXTENSION : &str = "rs"; 
+
+/// 列出目录下指定扩展名的脚本；目录不存在时返回空列表
+pub fn scan(root: &str, extension: &str) -> Vec<String> {
+    match std::fs::read_dir(root) {
+        Ok(dir) => {
+            dir.into_iter().flat_map(|f| f.ok().map(|f| f.path())).filter(|f| f.extension() == Some(extension.as_ref()))
+                .map(OsString::from)
+                .map(|f| f.to_string_lossy().into_owned())
+                .collect()
+        }
+        Err(_) => Vec::new(), 
+    }
+}
+
+/// 按名称查找脚本：可以是完整路径、文件名或不带扩展名的文件名
+pub fn find(root: &str, extension: &str, name: &str) -> Option<String> {
+    let scripts = scan(root, extension); 
+    if let Some(s) = scripts.iter().find(|s| s.as_str() == name) {
+        return Some(s.clone()); 
+    }
+    scripts.into_iter().find(|s| {
+        let p = Path::new(s); 
+        p.file_name() == Some(name.as_ref()) || p.file_stem() == Some(name.as_ref())
+    })
+}
@@ -0,0 +1,123 @@
+//! 脚本搜索：按文件名、清单标签与说明做模糊匹配
+
+use std::path::Path;
+
+use crate::manifest::Manifest;
+
+/// 最近使用的脚本保留的个数
+pub const RECENT_SCRIPTS : usize = 5; 
+
+/// 模糊匹配：query 的字符按顺序出现在 text 中即匹配（忽略大小写）。
+/// 连续匹配与单词开头的匹配得分更高；不匹配时返回 None
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let query : Vec<char> = query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()).collect(); 
+    if query.is_empty() {
+        return Some(0); 
+    }
+    let text : Vec<char> = text.chars().flat_map(char::to_lowercase).collect(); 
+    let mut score = 0; 
+    let mut q = 0; 
+    let mut previous : Option<usize> = None; 
+    for (i, c) in text.iter().enumerate() {
+        if q == query.len() {
+            break; 
+        }
+        if *c != query[q] {
+            continue; 
+        }
+        score += 1; 
+        if previous == Some(i.wrapping_sub(1)) {
+            score += 5; 
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 3; 
+        }
+        previous = Some(i); 
+        q += 1; 
+    }
+    if q < query.len() {
+        return None; 
+    }
+    // 越短的文本越精确
+    Some(score * 100 - text.len() as i64)
+}
+
+/// 脚本与查询的匹配得分：文件名优先，其次是标签，最后是说明与完整路径
+pub fn script_score(query: &str, script: &str, manifest: Option<&Manifest>) -> Option<i64> {
+    let name = Path::new(script).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(); 
+    let mut best = fuzzy_score(query, &name).map(|s| s * 4); 
+    let mut consider = |score: Option<i64>| {
+        if score > best {
+            best = score; 
+        }
+    }; 
+    if let Some(m) = manifest {
+        for tag in m.tags.iter() {
+            consider(fuzzy_score(query, tag).map(|s| s * 3)); 
+        }
+        consider(fuzzy_score(query, &m.description).map(|s| s * 2)); 
+    }
+    consider(fuzzy_score(query, script)); 
+    best
+}
+
+/// 按得分从高到低排列匹配的脚本
+pub fn search<'a>(query: &str, scripts: &'a [String], manifest: impl Fn(&str) -> Option<&'a Manifest>) -> Vec<&'a String> {
+    let mut matches : Vec<(i64, &String)> = scripts.iter()
+        .filter_map(|s| script_score(query, s, manifest(s)).map(|score| (score, s)))
+        .collect(); 
+    matches.sort_by_key(|m| std::cmp::Reverse(m.0)); 
+    matches.into_iter().map(|(_, s)| s).collect()
+}
+
+/// 把 script 移到最近使用列表的最前面
+pub fn push_recent(recent: &mut Vec<String>, script: &str) {
+    recent.retain(|s| s != script); 
+    recent.insert(0, script.to_string()); 
+    recent.truncate(RECENT_SCRIPTS); 
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_characters_in_order() {
+        assert!(fuzzy_score("blr", "gaussian_blur.py").is_some()); 
+        assert!(fuzzy_score("BLUR", "gaussian_blur.py").is_some()); 
+        assert!(fuzzy_score("g blur", "gaussian_blur.py").is_some()); 
+        assert_eq!(fuzzy_score("rulb", "gaussian_blur.py"), None); 
+        assert_eq!(fuzzy_score("blurx", "gaussian_blur.py"), None); 
+        assert_eq!(fuzzy_score("", "anything"), Some(0)); 
+        assert_eq!(fuzzy_score("  ", "anything"), Some(0)); 
+    }
+
+    #[test]
+    fn prefers_contiguous_and_word_start_matches() {
+        let contiguous = fuzzy_score("blur", "blur.py").unwrap(); 
+        let scattered = fuzzy_score("blur", "b_l_u_r.py").unwrap(); 
+        assert!(contiguous > scattered); 
+        let word_start = fuzzy_score("st", "style_transfer.py").unwrap(); 
+        let inner = fuzzy_score("st", "contrast.py").unwrap(); 
+        assert!(word_start > inner); 
+        assert!(fuzzy_score("blur", "blur.py") > fuzzy_score("blur", "blur_more.py")); 
+    }
+
+    #[test]
+    fn ignores_case_of_non_ascii_text() {
+        assert!(fuzzy_score("ÉCL", "éclair.py").is_some()); 
+        assert!(fuzzy_score("风格", "风格迁移.py").is_some()); 
+    }
+
+    #[test]
+    fn keeps_recent_scripts_unique_and_bounded() {
+        let mut recent = Vec::new(); 
+        for n in 0..RECENT_SCRIPTS + 2 {
+            push_recent(&mut recent, &format!("s{}", n)); 
+        }
+        push_recent(&mut recent, "s3"); 
+        assert_eq!(recent.len(), RECENT_SCRIPTS); 
+        assert_eq!(recent[0], "s3"); 
+        assert_eq!(recent.iter().filter(|s| *s == "s3").count(), 1); 
+    }
+}
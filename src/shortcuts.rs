@@ -0,0 +1,231 @@
+//! 快捷键：动作与按键组合的对应关系，可在配置文件的 `[shortcuts]` 表中修改
+//!
+//! ```toml
+//! [shortcuts]
+//! execute = "Ctrl+Enter"
+//! refresh_scripts = "F5"
+//! ```
+
+use std::collections::BTreeMap;
+
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+
+/// 可以绑定快捷键的动作
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Action {
+    Execute, 
+    Cancel, 
+    OpenInput1, 
+    OpenInput2, 
+    CopyResult, 
+    PreviousScript, 
+    NextScript, 
+    ToggleMode, 
+    RefreshScripts, 
+    ShowHelp, 
+}
+
+impl Action {
+    pub const ALL : [Action; 10] = [
+        Action::Execute, Action::Cancel, Action::OpenInput1, Action::OpenInput2, Action::CopyResult, 
+        Action::PreviousScript, Action::NextScript, Action::ToggleMode, Action::RefreshScripts, Action::ShowHelp, 
+    ]; 
+
+    /// 配置文件中的名称
+    pub fn key(&self) -> &'static str {
+        match self {
+            Action::Execute => "execute", 
+            Action::Cancel => "cancel", 
+            Action::OpenInput1 => "open_input_1", 
+            Action::OpenInput2 => "open_input_2", 
+            Action::CopyResult => "copy_result", 
+            Action::PreviousScript => "previous_script", 
+            Action::NextScript => "next_script", 
+            Action::ToggleMode => "toggle_mode", 
+            Action::RefreshScripts => "refresh_scripts", 
+            Action::ShowHelp => "show_help", 
+        }
+    }
+
+    /// 帮助中显示的说明
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Execute => "Execute the selected script", 
+            Action::Cancel => "Cancel running scripts", 
+            Action::OpenInput1 => "Open the first input image", 
+            Action::OpenInput2 => "Open the second input image", 
+            Action::CopyResult => "Copy the result image", 
+            Action::PreviousScript => "Select the previous script", 
+            Action::NextScript => "Select the next script", 
+            Action::ToggleMode => "Toggle Python / Native mode", 
+            Action::RefreshScripts => "Refresh the script list", 
+            Action::ShowHelp => "Show this help", 
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.key() == key)
+    }
+
+    fn default_shortcut(&self) -> KeyboardShortcut {
+        let (modifiers, key) = match self {
+            Action::Execute => (Modifiers::COMMAND, Key::Enter), 
+            Action::Cancel => (Modifiers::NONE, Key::Escape), 
+            Action::OpenInput1 => (Modifiers::COMMAND, Key::Num1), 
+            Action::OpenInput2 => (Modifiers::COMMAND, Key::Num2), 
+            Action::CopyResult => (Modifiers::COMMAND, Key::C), 
+            Action::PreviousScript => (Modifiers::NONE, Key::ArrowUp), 
+            Action::NextScript => (Modifiers::NONE, Key::ArrowDown), 
+            Action::ToggleMode => (Modifiers::COMMAND, Key::M), 
+            Action::RefreshScripts => (Modifiers::NONE, Key::F5), 
+            Action::ShowHelp => (Modifiers::NONE, Key::F1), 
+        }; 
+        KeyboardShortcut::new(modifiers, key)
+    }
+}
+
+/// 可以作为快捷键的按键
+const KEYS : [Key; 69] = [
+    Key::ArrowDown, Key::ArrowLeft, Key::ArrowRight, Key::ArrowUp, 
+    Key::Escape, Key::Tab, Key::Backspace, Key::Enter, Key::Space, 
+    Key::Insert, Key::Delete, Key::Home, Key::End, Key::PageUp, Key::PageDown, 
+    Key::Minus, Key::PlusEquals, 
+    Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9, 
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M, 
+    Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z, 
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12, 
+    Key::F13, Key::F14, Key::F15, Key::F16, 
+]; 
+
+/// 解析 `Ctrl+Shift+K` 形式的按键组合；Ctrl 与 Cmd 都表示平台的命令键
+pub fn parse(text: &str) -> Result<KeyboardShortcut, String> {
+    let mut modifiers = Modifiers::NONE; 
+    let mut key = None; 
+    for part in text.split('+').map(str::trim) {
+        match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" | "cmd" | "command" => modifiers = modifiers | Modifiers::COMMAND, 
+            "shift" => modifiers = modifiers | Modifiers::SHIFT, 
+            "alt" | "option" => modifiers = modifiers | Modifiers::ALT, 
+            name => {
+                if key.is_some() {
+                    return Err(format!("`{}` has more than one key", text)); 
+                }
+                let name = match name {
+                    "esc" => "escape", 
+                    "return" => "enter", 
+                    "del" => "delete", 
+                    "arrowup" => "up", 
+                    "arrowdown" => "down", 
+                    "arrowleft" => "left", 
+                    "arrowright" => "right", 
+                    other => other, 
+                }; 
+                key = Some(KEYS.into_iter().find(|k| k.name().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("unknown key `{}` in `{}`", part, text))?); 
+            }
+        }
+    }
+    let key = key.ok_or_else(|| format!("`{}` has no key", text))?; 
+    Ok(KeyboardShortcut::new(modifiers, key))
+}
+
+/// 动作到按键组合的对应关系
+#[derive(Clone, Debug)]
+pub struct Shortcuts {
+    pub bindings: BTreeMap<Action, KeyboardShortcut>, 
+}
+
+impl Default for Shortcuts {
+    fn default() -> Self {
+        Shortcuts { bindings: Action::ALL.iter().map(|a| (*a, a.default_shortcut())).collect() }
+    }
+}
+
+impl Shortcuts {
+    /// 在默认快捷键上应用配置；返回无法识别的项
+    pub fn from_config(config: &BTreeMap<String, String>) -> (Shortcuts, Vec<String>) {
+        let mut shortcuts = Shortcuts::default(); 
+        let mut problems = Vec::new(); 
+        for (name, text) in config {
+            let Some(action) = Action::from_key(name) else {
+                problems.push(format!("`shortcuts`: unknown action `{}`", name)); 
+                continue; 
+            }; 
+            match parse(text) {
+                Ok(s) => {
+                    shortcuts.bindings.insert(action, s); 
+                }
+                Err(e) => problems.push(format!("`shortcuts.{}`: {}", name, e)), 
+            }
+        }
+        (shortcuts, problems)
+    }
+
+    /// 本帧按下且 enabled 允许的动作；只有这些按键被消耗，其余照常传给其它控件
+    pub fn pressed(&self, ctx: &egui::Context, enabled: impl Fn(Action) -> bool) -> Vec<Action> {
+        ctx.input_mut(|i| {
+            self.bindings.iter().filter(|(a, s)| enabled(**a) && i.consume_shortcut(s)).map(|(a, _)| *a).collect()
+        })
+    }
+
+    /// 快捷键的显示文字
+    pub fn text(&self, ctx: &egui::Context, action: Action) -> String {
+        self.bindings.get(&action).map(|s| ctx.format_shortcut(s)).unwrap_or_default()
+    }
+
+    /// 快捷键帮助窗口
+    pub fn help_window(&self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new("Keyboard Shortcuts").open(open).collapsible(false).resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0., 0.]).show(ctx, |ui| {
+            egui::Grid::new("shortcut_help").num_columns(2).striped(true).show(ui, |ui| {
+                for action in Action::ALL {
+                    ui.monospace(self.text(ctx, action)); 
+                    ui.label(action.description()); 
+                    ui.end_row(); 
+                }
+            }); 
+            ui.separator(); 
+            ui.label("Shortcuts can be changed in the [shortcuts] table of the configuration file."); 
+        }); 
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modifiers_and_keys() {
+        assert_eq!(parse("Ctrl+Enter"), Ok(KeyboardShortcut::new(Modifiers::COMMAND, Key::Enter))); 
+        assert_eq!(parse("cmd + shift + s"), Ok(KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::S))); 
+        assert_eq!(parse("Alt+1"), Ok(KeyboardShortcut::new(Modifiers::ALT, Key::Num1))); 
+        assert_eq!(parse("F5"), Ok(KeyboardShortcut::new(Modifiers::NONE, Key::F5))); 
+    }
+
+    #[test]
+    fn accepts_key_aliases() {
+        assert_eq!(parse("Esc"), parse("Escape")); 
+        assert_eq!(parse("Return"), parse("Enter")); 
+        assert_eq!(parse("ArrowUp"), Ok(KeyboardShortcut::new(Modifiers::NONE, Key::ArrowUp))); 
+        assert_eq!(parse("Del"), Ok(KeyboardShortcut::new(Modifiers::NONE, Key::Delete))); 
+    }
+
+    #[test]
+    fn rejects_invalid_shortcuts() {
+        assert!(parse("").is_err()); 
+        assert!(parse("Ctrl").is_err()); 
+        assert!(parse("Ctrl+").is_err()); 
+        assert!(parse("A+B").is_err()); 
+        assert!(parse("Ctrl+Nope").is_err()); 
+    }
+
+    #[test]
+    fn reports_config_problems() {
+        let config = [("execute", "F9"), ("cancel", "Ctrl+Nope"), ("no_such_action", "F1")]
+            .into_iter().map(|(a, s)| (a.to_string(), s.to_string())).collect(); 
+        let (shortcuts, problems) = Shortcuts::from_config(&config); 
+        assert_eq!(shortcuts.bindings[&Action::Execute], KeyboardShortcut::new(Modifiers::NONE, Key::F9)); 
+        assert_eq!(shortcuts.bindings[&Action::Cancel], Action::Cancel.default_shortcut()); 
+        assert_eq!(problems.len(), 2); 
+    }
+}
@@ -0,0 +1,149 @@
+//! 颜色统计窗口：选择一幅图像，在后台统计并绘制直方图
+
+use eframe::egui::{self, Color32, Pos2, Rect, Sense, Shape, Stroke};
+use futures::channel::oneshot;
+
+use crate::color_stats::ColorStats;
+use crate::image_loader;
+
+/// 直方图的高度
+const HISTOGRAM_HEIGHT : f32 = 120.; 
+
+/// 统计窗口状态
+pub struct StatsView {
+    /// 选中的图像名称；新结果到达时按名称刷新
+    pub source: Option<String>, 
+    /// 已统计的文件
+    pub path: Option<String>, 
+    pub rx: Option<oneshot::Receiver<Result<ColorStats, String>>>, 
+    pub stats: Option<ColorStats>, 
+    pub error: Option<String>, 
+    /// 直方图使用对数纵轴
+    pub log_scale: bool, 
+}
+
+impl StatsView {
+    pub fn new(source: Option<String>) -> StatsView {
+        StatsView { source, path: None, rx: None, stats: None, error: None, log_scale: false }
+    }
+
+    /// 在后台统计文件
+    pub fn load(&mut self, path: String) {
+        let (tx, rx) = oneshot::channel(); 
+        let p = path.clone(); 
+        std::thread::spawn(move || {
+            let result = image_loader::open_oriented(&p)
+                .map(|i| ColorStats::compute(&i.to_rgba8()))
+                .map_err(|e| format!("{}: {}", p, e)); 
+            let _ = tx.send(result); 
+        }); 
+        self.path = Some(path); 
+        self.rx = Some(rx); 
+        self.error = None; 
+    }
+
+    /// 名为 source 的图像有了新内容；是当前选中的图像时重新统计
+    pub fn refresh(&mut self, source: &str, path: &str) {
+        if self.source.as_deref() == Some(source) {
+            self.load(path.to_string()); 
+        }
+    }
+
+    fn poll(&mut self, ctx: &egui::Context) {
+        let Some(ref mut rx) = self.rx else {
+            return ; 
+        }; 
+        match rx.try_recv() {
+            Ok(None) => ctx.request_repaint(), 
+            Ok(Some(Ok(stats))) => {
+                self.stats = Some(stats); 
+                self.rx = None; 
+            }
+            Ok(Some(Err(e))) => {
+                self.error = Some(e); 
+                self.rx = None; 
+            }
+            Err(_) => {
+                self.rx = None; 
+            }
+        }
+    }
+
+    /// 显示窗口；sources 为可选的图像（名称，文件路径）
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool, sources: &[(String, String)]) {
+        self.poll(ctx); 
+        // 选中的图像换了文件，或者第一次显示
+        if let Some((_, path)) = sources.iter().find(|(n, _)| Some(n) == self.source.as_ref()) {
+            if self.path.as_ref() != Some(path) {
+                self.load(path.clone()); 
+            }
+        }
+        egui::Window::new("Color Statistics").id(egui::Id::new("color_statistics")).open(open)
+            .default_width(420.).resizable(true).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Image: "); 
+                egui::ComboBox::from_id_source("stats_source")
+                    .selected_text(self.source.clone().unwrap_or_else(|| "Select".to_string()))
+                    .show_ui(ui, |ui| {
+                        for (name, path) in sources {
+                            if ui.selectable_label(self.source.as_ref() == Some(name), name).on_hover_text(path.as_str()).clicked() {
+                                self.source = Some(name.clone()); 
+                                self.load(path.clone()); 
+                            }
+                        }
+                    }); 
+                if self.rx.is_some() {
+                    ui.spinner(); 
+                }
+            }); 
+            if let Some(ref e) = self.error {
+                ui.colored_label(Color32::RED, e); 
+            }
+            let Some(ref stats) = self.stats else {
+                return ; 
+            }; 
+            ui.checkbox(&mut self.log_scale, "Logarithmic"); 
+            let colors = [Color32::from_rgb(230, 60, 60), Color32::from_rgb(60, 200, 60), Color32::from_rgb(70, 110, 240), ui.visuals().text_color()]; 
+            histogram(ui, &stats.histograms[..3], &colors[..3], self.log_scale); 
+            histogram(ui, &stats.histograms[3..], &colors[3..], self.log_scale); 
+            egui::Grid::new("stats_channels").num_columns(3).show(ui, |ui| {
+                ui.label(""); 
+                ui.label("Mean"); 
+                ui.label("Std Dev"); 
+                ui.end_row(); 
+                for (c, color) in colors.iter().enumerate() {
+                    ui.colored_label(*color, ColorStats::CHANNELS[c]); 
+                    ui.label(format!("{:.2}", stats.mean[c])); 
+                    ui.label(format!("{:.2}", stats.stddev[c])); 
+                    ui.end_row(); 
+                }
+            }); 
+            ui.label(format!("{} × {}", stats.size[0], stats.size[1])); 
+            ui.separator(); 
+            ui.label("Dominant Colors: "); 
+            ui.horizontal_wrapped(|ui| {
+                for (color, share) in stats.palette.iter() {
+                    let (rect, response) = ui.allocate_exact_size([32., 32.].into(), Sense::hover()); 
+                    ui.painter().rect_filled(rect, 2., Color32::from_rgb(color[0], color[1], color[2])); 
+                    response.on_hover_text(format!("#{:02X}{:02X}{:02X}  {:.1}%", color[0], color[1], color[2], share * 100.)); 
+                }
+            }); 
+        }); 
+    }
+}
+
+/// 绘制若干通道叠加的直方图
+fn histogram(ui: &mut egui::Ui, histograms: &[[u32; 256]], colors: &[Color32], log_scale: bool) {
+    let (rect, _) = ui.allocate_exact_size([ui.available_width(), HISTOGRAM_HEIGHT].into(), Sense::hover()); 
+    let painter = ui.painter_at(rect); 
+    painter.rect_filled(rect, 2., ui.visuals().extreme_bg_color); 
+    let scale = |v: u32| if log_scale { (v as f32 + 1.).ln() } else { v as f32 }; 
+    let max = histograms.iter().flat_map(|h| h.iter()).map(|&v| scale(v)).fold(0f32, f32::max).max(1.); 
+    for (h, color) in histograms.iter().zip(colors) {
+        let points = h.iter().enumerate().map(|(i, &v)| {
+            Pos2::new(rect.left() + rect.width() * i as f32 / 255., rect.bottom() - rect.height() * scale(v) / max)
+        }).collect(); 
+        painter.add(Shape::line(points, Stroke::new(1.5, *color))); 
+    }
+    painter.rect_stroke(Rect::from_min_max(rect.min, rect.max), 2., ui.visuals().widgets.noninteractive.bg_stroke); 
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use crate::script_execution::Executor;
+
+/// 参数扫描默认输出目录
+pub const SWEEP_OUTPUT_DIR : &str = "./outcome/sweep"; 
+/// 参数模板中的占位符
+pub const SWEEP_PLACEHOLDER : &str = "{}"; 
+/// 单次扫描最多展开的执行次数
+pub const SWEEP_MAX_RUNS : usize = 256; 
+
+/// 参数扫描：把模板中的占位符依次替换为每个取值
+#[derive(Clone, Debug, Default)]
+pub struct Sweep {
+    pub template: String, 
+    pub values: Vec<String>, 
+}
+
+/// 小数位数，用于格式化范围中的取值
+fn decimals(s: &str) -> usize {
+    s.split_once('.').map(|(_, f)| f.len()).unwrap_or(0)
+}
+
+/// 解析取值：逗号分隔的列表 `1, 2, 5`，或包含终点的范围 `start..end:step`（步长默认为 1）
+pub fn parse_values(spec: &str) -> Result<Vec<String>, String> {
+    let spec = spec.trim(); 
+    let Some((start, rest)) = spec.split_once("..") else {
+        let values : Vec<String> = spec.split(',').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect(); 
+        if values.is_empty() {
+            return Err("no values".to_string()); 
+        }
+        if values.len() > SWEEP_MAX_RUNS {
+            return Err(format!("too many values: {} (max {})", values.len(), SWEEP_MAX_RUNS)); 
+        }
+        return Ok(values); 
+    }; 
+    let (end, step) = rest.split_once(':').unwrap_or((rest, "1")); 
+    let (start, end, step) = (start.trim(), end.trim(), step.trim()); 
+    let parse = |v: &str| match v.parse::<f64>() {
+        Ok(x) if x.is_finite() => Ok(x), 
+        _ => Err(format!("invalid number: {}", v)), 
+    }; 
+    let (a, b, d) = (parse(start)?, parse(end)?, parse(step)?); 
+    if d <= 0. {
+        return Err(format!("step must be positive: {}", step)); 
+    }
+    if b < a {
+        return Err(format!("empty range: {}", spec)); 
+    }
+    // 先以浮点数比较上限（跨度过大时为 inf），避免转换为 usize 时饱和
+    let count = ((b - a) / d + 1e-9).floor() + 1.; 
+    if count > SWEEP_MAX_RUNS as f64 {
+        return Err(format!("too many values: {} (max {})", count, SWEEP_MAX_RUNS)); 
+    }
+    let count = count as usize; 
+    let precision = decimals(start).max(decimals(step)); 
+    Ok((0..count).map(|i| format!("{:.*}", precision, a + d * i as f64)).collect())
+}
+
+impl Sweep {
+    /// 每个取值对应的额外参数
+    pub fn arguments(&self) -> Vec<String> {
+        self.values.iter().map(|v| self.template.replace(SWEEP_PLACEHOLDER, v)).collect()
+    }
+
+    /// 以 base 为模板展开执行器，每次执行写入各自的输出文件
+    pub fn executors(&self, base: &Executor, output_dir: &str) -> Vec<Executor> {
+        self.arguments().into_iter().enumerate().map(|(n, other_args)| Executor {
+            output: base.output_settings.apply(Path::new(output_dir).join(format!("run-{}", n + 1))), 
+            other_args, 
+            ..base.clone()
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lists() {
+        assert_eq!(parse_values(" 1, 2 ,,5 ").unwrap(), ["1", "2", "5"]); 
+        assert!(parse_values(" , ").is_err()); 
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(parse_values("1..3").unwrap(), ["1", "2", "3"]); 
+        assert_eq!(parse_values("0..1:0.25").unwrap(), ["0.00", "0.25", "0.50", "0.75", "1.00"]); 
+        assert_eq!(parse_values("0.5..0.5").unwrap(), ["0.5"]); 
+        assert!(parse_values("3..1").is_err()); 
+        assert!(parse_values("1..3:0").is_err()); 
+        assert!(parse_values("1..3:-1").is_err()); 
+        assert!(parse_values("a..3").is_err()); 
+    }
+
+    #[test]
+    fn rejects_non_finite_numbers() {
+        for spec in ["inf..1", "0..inf", "0..1:inf", "nan..1", "0..nan", "0..1:nan", "-inf..0", "0..1e309"] {
+            assert!(parse_values(spec).is_err(), "{}", spec); 
+        }
+    }
+
+    #[test]
+    fn caps_number_of_values() {
+        assert_eq!(parse_values(&format!("1..{}", SWEEP_MAX_RUNS)).unwrap().len(), SWEEP_MAX_RUNS); 
+        assert!(parse_values(&format!("1..{}", SWEEP_MAX_RUNS + 1)).is_err()); 
+        assert!(parse_values("0..1:1e-300").is_err()); 
+        assert!(parse_values("-1e308..1e308").is_err()); 
+        let list = vec!["1"; SWEEP_MAX_RUNS + 1].join(","); 
+        assert!(parse_values(&list).is_err()); 
+    }
+}
@@ -0,0 +1,99 @@
+//! 右下角的短暂提示：后台线程通过 ToastSender 报告操作成功或失败
+
+use std::time::Duration;
+
+use eframe::egui::{self, Color32};
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// 提示显示的时间
+pub const TOAST_DURATION : Duration = Duration::from_secs(3); 
+/// 同时显示的最多条数
+const MAX_TOASTS : usize = 5; 
+
+/// 一条提示
+#[derive(Clone, Debug)]
+pub struct Toast {
+    pub text: String, 
+    pub success: bool, 
+    /// 第一次显示的时间（egui 时间，秒）
+    shown_at: Option<f64>, 
+}
+
+/// 可以跨线程发送提示；发送后请求重绘
+#[derive(Clone)]
+pub struct ToastSender {
+    tx: UnboundedSender<Toast>, 
+    ctx: egui::Context, 
+}
+
+impl ToastSender {
+    pub fn success(&self, text: impl Into<String>) {
+        self.send(text.into(), true); 
+    }
+
+    pub fn error(&self, text: impl Into<String>) {
+        self.send(text.into(), false); 
+    }
+
+    /// 按结果发送成功或失败提示
+    pub fn result<E: std::fmt::Display>(&self, result: Result<String, E>) {
+        match result {
+            Ok(text) => self.success(text), 
+            Err(e) => self.error(e.to_string()), 
+        }
+    }
+
+    fn send(&self, text: String, success: bool) {
+        let _ = self.tx.unbounded_send(Toast { text, success, shown_at: None }); 
+        self.ctx.request_repaint(); 
+    }
+}
+
+/// 等待显示与正在显示的提示
+pub struct Toasts {
+    tx: UnboundedSender<Toast>, 
+    rx: UnboundedReceiver<Toast>, 
+    list: Vec<Toast>, 
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::unbounded(); 
+        Toasts { tx, rx, list: Vec::new() }
+    }
+}
+
+impl Toasts {
+    pub fn sender(&self, ctx: &egui::Context) -> ToastSender {
+        ToastSender { tx: self.tx.clone(), ctx: ctx.clone() }
+    }
+
+    /// 收取新提示，绘制并移除过期的提示
+    pub fn show(&mut self, ctx: &egui::Context) {
+        while let Ok(Some(t)) = self.rx.try_next() {
+            self.list.push(t); 
+        }
+        let now = ctx.input(|i| i.time); 
+        let duration = TOAST_DURATION.as_secs_f64(); 
+        self.list.retain(|t| t.shown_at.map(|s| now - s < duration).unwrap_or(true)); 
+        if self.list.len() > MAX_TOASTS {
+            self.list.drain(..self.list.len() - MAX_TOASTS); 
+        }
+        if self.list.is_empty() {
+            return ; 
+        }
+        egui::Area::new("toasts").anchor(egui::Align2::RIGHT_BOTTOM, [-12., -12.]).interactable(false).show(ctx, |ui| {
+            for t in self.list.iter_mut() {
+                t.shown_at.get_or_insert(now); 
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    let (mark, color) = if t.success { ("✔", Color32::from_rgb(80, 180, 80)) } else { ("✖", Color32::from_rgb(220, 80, 80)) }; 
+                    ui.horizontal(|ui| {
+                        ui.colored_label(color, mark); 
+                        ui.label(&t.text); 
+                    }); 
+                }); 
+            }
+        }); 
+        ctx.request_repaint_after(Duration::from_millis(250)); 
+    }
+}
@@ -0,0 +1,221 @@
+//! 图像查看器：滚轮缩放、拖动平移、适应窗口 / 1:1，放大时显示像素网格
+
+use eframe::egui::{self, Color32, Pos2, Rect, Sense, Stroke, TextureOptions, Vec2};
+use eframe::epaint::{ColorImage, TextureHandle};
+use futures::channel::oneshot;
+use image::{ImageBuffer, Rgba};
+
+use crate::image_loader;
+use crate::inspector;
+
+/// 缩放范围（屏幕像素 / 图像像素）
+const MIN_ZOOM : f32 = 0.01; 
+const MAX_ZOOM : f32 = 64.; 
+/// 达到该缩放倍数时显示像素网格
+const GRID_ZOOM : f32 = 8.; 
+
+/// 后台读取的结果：纹理像素与原图尺寸
+type Loaded = (ImageBuffer<Rgba<u8>, Vec<u8>>, [u32; 2]); 
+
+/// 查看器窗口状态
+pub struct Viewer {
+    /// 查看的文件
+    pub path: String, 
+    /// 全分辨率纹理；原图超过显卡纹理限制时会缩小
+    pub texture: Option<TextureHandle>, 
+    /// 纹理的像素，用于像素检查
+    pub buffer: Option<ImageBuffer<Rgba<u8>, Vec<u8>>>, 
+    /// 后台读取通道
+    pub rx: Option<oneshot::Receiver<Loaded>>, 
+    /// 原图尺寸
+    pub size: [u32; 2], 
+    /// 缩放倍数
+    pub zoom: f32, 
+    /// 显示在画布中心的图像坐标（纹理像素）
+    pub center: Vec2, 
+    /// 随窗口大小自动适应
+    pub fit: bool, 
+    /// 读取失败
+    pub error: Option<String>, 
+}
+
+impl Viewer {
+    /// 在后台读取全分辨率图像，最长边不超过 max_side
+    pub fn open(path: String, max_side: usize) -> Viewer {
+        let (tx, rx) = oneshot::channel(); 
+        let p = path.clone(); 
+        std::thread::spawn(move || {
+            match image_loader::open_oriented(&p) {
+                Ok(image) => {
+                    // 方向已校正，尺寸即显示的宽高
+                    let size = [image.width(), image.height()]; 
+                    let _ = tx.send((image_loader::preview(image.to_rgba8(), max_side as u32), size)); 
+                }
+                Err(e) => {
+                    eprintln!("Error: {:?}", e); 
+                }
+            }
+        }); 
+        Viewer {
+            path, 
+            texture: None, 
+            buffer: None, 
+            rx: Some(rx), 
+            size: [0, 0], 
+            zoom: 1., 
+            center: Vec2::ZERO, 
+            fit: true, 
+            error: None, 
+        }
+    }
+
+    /// 纹理尺寸
+    pub fn texture_size(&self) -> Vec2 {
+        self.texture.as_ref().map(|t| t.size_vec2()).unwrap_or(Vec2::ZERO)
+    }
+
+    /// 原图一个像素对应一个屏幕像素时的缩放倍数；纹理被缩小时小于 1
+    pub fn actual_size_zoom(&self) -> f32 {
+        let texture = self.texture_size(); 
+        if self.size[0] == 0 || texture.x <= 0. {
+            return 1.; 
+        }
+        texture.x / self.size[0] as f32
+    }
+
+    /// 检查后台读取结果
+    fn poll(&mut self, ctx: &egui::Context) {
+        let Some(ref mut rx) = self.rx else {
+            return ; 
+        }; 
+        match rx.try_recv() {
+            Ok(None) => ctx.request_repaint(), 
+            Ok(Some((ib, size))) => {
+                let ci = ColorImage::from_rgba_unmultiplied([ib.width() as usize, ib.height() as usize], &ib); 
+                let options = TextureOptions { magnification: egui::TextureFilter::Nearest, minification: egui::TextureFilter::Linear }; 
+                self.texture = Some(ctx.load_texture(format!("viewer:{}", self.path), ci, options)); 
+                self.size = size; 
+                self.buffer = Some(ib); 
+                self.rx = None; 
+            }
+            Err(_) => {
+                self.error = Some(format!("Cannot read {}", self.path)); 
+                self.rx = None; 
+            }
+        }
+    }
+
+    /// 适应画布
+    fn fit_to(&mut self, rect: Rect) {
+        let size = self.texture_size(); 
+        if size.x > 0. && size.y > 0. {
+            self.zoom = (rect.width() / size.x).min(rect.height() / size.y).clamp(MIN_ZOOM, MAX_ZOOM); 
+        }
+        self.center = size / 2.; 
+    }
+
+    /// 以画布上的点为中心缩放
+    fn zoom_at(&mut self, rect: Rect, pos: Pos2, factor: f32) {
+        let anchor = self.center + (pos - rect.center()) / self.zoom; 
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM); 
+        self.center = anchor - (pos - rect.center()) / self.zoom; 
+        self.fit = false; 
+    }
+
+    /// 画布上的点对应的图像坐标（纹理像素）
+    pub fn screen_to_image(&self, rect: Rect, pos: Pos2) -> Vec2 {
+        self.center + (pos - rect.center()) / self.zoom
+    }
+
+    /// 图像在画布上占据的区域
+    pub fn image_rect(&self, rect: Rect) -> Rect {
+        Rect::from_min_size(rect.center() - self.center * self.zoom, self.texture_size() * self.zoom)
+    }
+
+    /// 显示窗口；open 为 false 时窗口已关闭
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        self.poll(ctx); 
+        let title = std::path::Path::new(&self.path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(); 
+        egui::Window::new(format!("Viewer - {}", title)).id(egui::Id::new("image_viewer")).open(open)
+            .default_size([800., 600.]).resizable(true).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.selectable_label(self.fit, "Fit").clicked() {
+                    self.fit = true; 
+                }
+                let actual = self.actual_size_zoom(); 
+                if ui.selectable_label(!self.fit && self.zoom == actual, "1:1").on_hover_text("One original pixel per screen pixel").clicked() {
+                    self.fit = false; 
+                    self.zoom = actual; 
+                }
+                ui.label(format!("{:.0}%", self.zoom / actual * 100.)); 
+                if actual != 1. {
+                    ui.label("(downscaled texture)").on_hover_text("The image exceeds the GPU texture limit and is shown downscaled"); 
+                }
+                ui.label(format!("{} × {}", self.size[0], self.size[1])); 
+            }); 
+            ui.separator(); 
+            if let Some(ref e) = self.error {
+                ui.colored_label(Color32::RED, e); 
+                return ; 
+            }
+            let Some(texture) = self.texture.clone() else {
+                ui.centered_and_justified(|ui| ui.spinner()); 
+                return ; 
+            }; 
+            let (rect, response) = ui.allocate_exact_size(ui.available_size().max(Vec2::splat(64.)), Sense::click_and_drag()); 
+            if self.fit {
+                self.fit_to(rect); 
+            }
+            if response.hovered() {
+                let scroll = ui.input(|i| i.scroll_delta.y); 
+                let pinch = ui.input(|i| i.zoom_delta()); 
+                let factor = (scroll / 200.).exp() * pinch; 
+                if factor != 1. {
+                    if let Some(pos) = response.hover_pos() {
+                        self.zoom_at(rect, pos, factor); 
+                    }
+                }
+            }
+            if response.dragged() {
+                self.center -= response.drag_delta() / self.zoom; 
+                self.fit = false; 
+            }
+            if response.double_clicked() {
+                self.fit = !self.fit; 
+                if !self.fit {
+                    if let Some(pos) = response.interact_pointer_pos() {
+                        let factor = self.actual_size_zoom() / self.zoom; 
+                        self.zoom_at(rect, pos, factor); 
+                    }
+                }
+            }
+            let painter = ui.painter_at(rect); 
+            painter.rect_filled(rect, 0., ui.visuals().extreme_bg_color); 
+            let image_rect = self.image_rect(rect); 
+            painter.image(texture.id(), image_rect, Rect::from_min_max(Pos2::ZERO, Pos2::new(1., 1.)), Color32::WHITE); 
+            if self.zoom >= GRID_ZOOM {
+                // 只画可见范围内的像素边界
+                let size = self.texture_size(); 
+                let visible = image_rect.intersect(rect); 
+                let min = self.screen_to_image(rect, visible.min).floor().max(Vec2::ZERO); 
+                let max = self.screen_to_image(rect, visible.max).ceil().min(size); 
+                let stroke = Stroke::new(1., Color32::from_black_alpha(96)); 
+                let mut x = min.x; 
+                while x <= max.x {
+                    let sx = image_rect.min.x + x * self.zoom; 
+                    painter.line_segment([Pos2::new(sx, visible.min.y), Pos2::new(sx, visible.max.y)], stroke); 
+                    x += 1.; 
+                }
+                let mut y = min.y; 
+                while y <= max.y {
+                    let sy = image_rect.min.y + y * self.zoom; 
+                    painter.line_segment([Pos2::new(visible.min.x, sy), Pos2::new(visible.max.x, sy)], stroke); 
+                    y += 1.; 
+                }
+            }
+            if let Some(ref buffer) = self.buffer {
+                inspector::inspect(ui, &response, buffer, self.size, image_rect, true); 
+            }
+        }); 
+    }
+}